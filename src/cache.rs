@@ -1,11 +1,12 @@
 use crate::{
     log,
     view::Error,
-    yt::{Channel, ChannelInfo, Video, VideoWatchLater},
+    yt::{Channel, ChannelInfo, RefreshStatus, Video, VideoWatchLater},
     Channels,
 };
 
 use std::{
+    collections::HashMap,
     fs::{self, File},
     io::{BufReader, BufWriter},
     path::{Path, PathBuf},
@@ -24,7 +25,7 @@ pub fn load_channel(value: &ChannelInfo) -> Result<Channel, Error> {
     let file = File::open(json_file).map_err(|_| Error::FileBadAccess)?;
 
     serde_json::from_reader(BufReader::new(file))
-        .map(|videos| Channel::new(value.name.clone(), value.id.clone(), videos))
+        .map(|videos| Channel::from_info(value, videos))
         .map_err(|_| Error::JsonParsing)
 }
 
@@ -45,6 +46,28 @@ pub fn fetch_cached_channels() -> Option<Vec<ChannelInfo>> {
     }
 }
 
+pub fn fetch_refresh_statuses() -> HashMap<String, RefreshStatus> {
+    let Ok(root) = data_directory() else {
+        return HashMap::new();
+    };
+
+    File::open(root.join("refreshes.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+pub fn fetch_upcoming_videos() -> HashMap<String, Vec<Video>> {
+    let Ok(root) = data_directory() else {
+        return HashMap::new();
+    };
+
+    File::open(root.join("upcoming.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
 pub fn fetch_watch_later_videos() -> Vec<VideoWatchLater> {
     let Ok(root) = data_directory() else {
         log::err("Could not retrieve local data directory. Watch later cannot be enabled!");
@@ -105,6 +128,32 @@ pub fn cache_channels(channels: &Channels) -> Result<(), Error> {
             }
         });
 
+        let statuses: HashMap<&str, &RefreshStatus> = channels
+            .iter()
+            .map(|channel| (channel.id.as_str(), &channel.refresh))
+            .collect();
+
+        match File::create(root.join("refreshes.json")) {
+            Ok(file) => serde_json::to_writer_pretty(BufWriter::new(file), &statuses)
+                .map_err(|_| Error::JsonParsing)?,
+            Err(err) => log::err(format!(
+                "Error on caching refresh statuses!\nError: {}",
+                err
+            )),
+        }
+
+        let upcoming: HashMap<&str, &Vec<Video>> = channels
+            .iter()
+            .filter(|channel| !channel.upcoming.is_empty())
+            .map(|channel| (channel.id.as_str(), &channel.upcoming))
+            .collect();
+
+        match File::create(root.join("upcoming.json")) {
+            Ok(file) => serde_json::to_writer_pretty(BufWriter::new(file), &upcoming)
+                .map_err(|_| Error::JsonParsing)?,
+            Err(err) => log::err(format!("Error on caching upcoming videos!\nError: {}", err)),
+        }
+
         let channels: Vec<ChannelInfo> = channels.iter().map(ChannelInfo::from).collect();
 
         serde_json::to_writer_pretty(BufWriter::new(file), &channels)
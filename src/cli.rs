@@ -0,0 +1,412 @@
+use std::path::PathBuf;
+
+use crate::{
+    cache,
+    config::Config,
+    filters::Filters,
+    history::{self, HistoryEntry},
+    importers, log,
+    output::{VideoOutput, VideosOutput},
+    progress::ProgressStore,
+    updates::{self, fetch_updates},
+    utilities::expand_path,
+    view::{Error, Visibility},
+    views::player_view,
+    yt::{channel_id_from_url, playlist_id_from_url, Channel, ChannelInfo, ChannelTab, Channels},
+};
+
+const USAGE: &str = "Usage: yt-feeds [COMMAND]
+
+Without a command, the interactive application is started.
+
+Commands:
+  refresh                   Fetch new videos for every subscribed channel
+  list channels             List subscribed channels as '<id>\\t<name>'
+  list feed                 List the subscription feed as '<id>\\t<channel>\\t<upload>\\t<title>'
+  list channel <channel-id> [videos|streams|shorts]
+                            List one tab of a single channel's feed, videos by default
+  list watch-later          List the watch later videos
+  subscribe <url>           Subscribe to a channel by its URL or ID, or to a playlist by its URL
+  unsubscribe <id>          Unsubscribe from a channel or playlist
+  play <video-id>           Play a video with mpv, saving its watch progress
+  import <file>             Subscribe to every channel in an OPML, Google Takeout, NewPipe or FreeTube export
+
+List Options:
+  --unwatched               Only list videos that have not been watched
+  --hidden                  Also list videos and channels hidden by the [filters] rules
+  --json                    Print videos as versioned JSON instead of tab separated lines";
+
+const FLAGS: [&str; 3] = ["--unwatched", "--hidden", "--json"];
+
+pub enum Listing {
+    Channels,
+    Feed,
+    Channel(String, ChannelTab),
+    WatchLater,
+}
+
+pub enum Command {
+    Refresh,
+    List {
+        listing: Listing,
+        unwatched: bool,
+        hidden: bool,
+        json: bool,
+    },
+    Subscribe(String),
+    Unsubscribe(String),
+    Play(String),
+    Import(PathBuf),
+}
+
+pub fn parse(args: &[String]) -> Result<Option<Command>, String> {
+    // Flags may come anywhere after the command, so they are read apart from the positional arguments.
+    // Only known flags are taken out, as video IDs can start with dashes too
+    let (flags, positional): (Vec<&str>, Vec<&str>) = args
+        .iter()
+        .map(String::as_str)
+        .partition(|arg| FLAGS.contains(arg));
+    let has_flag = |flag: &str| flags.contains(&flag);
+
+    let Some(command) = positional.first() else {
+        return if flags.is_empty() {
+            Ok(None)
+        } else {
+            Err(USAGE.to_owned())
+        };
+    };
+
+    let command = match (*command, &positional[1..]) {
+        ("refresh", []) => Command::Refresh,
+        ("list", [listing, rest @ ..]) => Command::List {
+            listing: match (*listing, rest) {
+                ("channels", []) => Listing::Channels,
+                ("feed", []) => Listing::Feed,
+                ("channel", [id]) => Listing::Channel(id.to_string(), ChannelTab::Videos),
+                ("channel", [id, tab]) => Listing::Channel(id.to_string(), parse_tab(tab)?),
+                ("watch-later", []) => Listing::WatchLater,
+                _ => return Err(USAGE.to_owned()),
+            },
+            unwatched: has_flag("--unwatched"),
+            hidden: has_flag("--hidden"),
+            json: has_flag("--json"),
+        },
+        ("subscribe", [url]) => Command::Subscribe(url.to_string()),
+        ("unsubscribe", [id]) => Command::Unsubscribe(id.to_string()),
+        ("play", [id]) => Command::Play(id.to_string()),
+        ("import", [path]) => Command::Import(expand_path(path)),
+        _ => return Err(USAGE.to_owned()),
+    };
+
+    Ok(Some(command))
+}
+
+pub fn run(command: Command, config: &Config) -> Result<(), String> {
+    let mut channels = Channels::new(&cache::fetch_cached_channels().unwrap_or_default());
+    let mut watch_later = cache::fetch_watch_later_videos();
+    let mut progress = ProgressStore::load_or_migrate(&channels, &watch_later);
+    progress.hydrate(&mut channels, &mut watch_later);
+    let filters = Filters::new(&config.filters);
+    filters.apply(&mut channels, &mut watch_later);
+
+    match command {
+        Command::Refresh => {
+            let mut refresh = fetch_updates(
+                config.source(),
+                channels.iter().map(|channel| channel.into()).collect(),
+                &config.channel_tabs(),
+                config.videos_per_channel,
+                config.refresh_workers,
+            );
+
+            let mut updated = 0;
+            while let Some(update) = refresh.next_update(true) {
+                let name = update.channel.name.clone();
+                match updates::merge_update(&mut channels, update, &progress, &filters) {
+                    Ok(Some(_)) => updated += 1,
+                    // Unsubscribed from while refreshing
+                    Ok(None) => (),
+                    Err(err) => eprintln!("Could not refresh '{}': {}", name, err),
+                }
+            }
+
+            println!("Refreshed {}/{} channels", updated, channels.len());
+            cache::cache_channels(&channels).map_err(|err| err.to_string())
+        }
+        Command::List {
+            listing: Listing::Channels,
+            ..
+        } => {
+            channels
+                .iter()
+                .for_each(|channel| println!("{}\t{}", channel.id, channel.name));
+            Ok(())
+        }
+        Command::List {
+            listing,
+            unwatched,
+            hidden,
+            json,
+        } => {
+            let visibility = Visibility {
+                hide_watched: unwatched,
+                show_hidden: hidden,
+            };
+
+            let videos: Vec<VideoOutput> = match listing {
+                Listing::Channel(id, tab) => {
+                    let id = channel_id_from_url(&id)
+                        .or_else(|| playlist_id_from_url(&id))
+                        .unwrap_or(id);
+                    let channel = channels
+                        .channel_by_id(&id)
+                        .ok_or(format!("You are not subscribed to '{}'", id))?;
+
+                    channel
+                        .videos
+                        .iter()
+                        .filter(|video| video.tab == tab && visibility.shows(video))
+                        .map(|video| VideoOutput::new(video, &channel.id, &channel.name))
+                        .collect()
+                }
+                Listing::WatchLater => watch_later
+                    .iter()
+                    .filter(|later| visibility.shows(&later.video))
+                    .map(VideoOutput::from)
+                    .collect(),
+                _ => channels
+                    .videos_mixed()
+                    .into_iter()
+                    .filter(|(_, channel, video)| {
+                        video.tab == ChannelTab::Videos
+                            && visibility.shows(video)
+                            && (hidden || !channel.excluded_from_mixed)
+                    })
+                    .map(|(_, channel, video)| VideoOutput::new(video, &channel.id, &channel.name))
+                    .collect(),
+            };
+
+            if json {
+                let output = serde_json::to_string_pretty(&VideosOutput::new(videos))
+                    .map_err(|err| err.to_string())?;
+                println!("{}", output);
+            } else {
+                videos.iter().for_each(|video| {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        video.id, video.channel_name, video.upload, video.title
+                    )
+                });
+            }
+            Ok(())
+        }
+        Command::Subscribe(url) => {
+            let url = if url.starts_with("UC") && !url.contains('/') {
+                format!("https://www.youtube.com/channel/{}", url)
+            } else {
+                url
+            };
+
+            let info = if playlist_id_from_url(&url).is_some() {
+                config
+                    .source()
+                    .fetch_playlist_from_url(&url)
+                    .map_err(|err| format!("Could not find playlist '{}'. Error: {}", url, err))?
+            } else {
+                config
+                    .source()
+                    .fetch_channel_from_url(&url)
+                    .map_err(|err| format!("Could not find channel '{}'. Error: {}", url, err))?
+            };
+
+            if channels.has_channel(&info.id) {
+                return Err(format!("You are already subscribed to {}!", info.name));
+            }
+
+            let feed = config
+                .source()
+                .fetch_feed(info.kind, &info.id, config.videos_per_channel, None)
+                .map_err(|err| {
+                    format!(
+                        "Could not load in feed for channel: '{}' with command 'yt-dlp'.\nError: {}",
+                        info.id, err
+                    )
+                })?;
+
+            println!("Subscribed to {} ({})", info.name, info.id);
+            channels.subscribe(Channel::from_info(&info, feed), &progress, &filters);
+            cache::cache_channels(&channels).map_err(|err| err.to_string())
+        }
+        Command::Unsubscribe(id) => {
+            let id = channel_id_from_url(&id)
+                .or_else(|| playlist_id_from_url(&id))
+                .unwrap_or(id);
+            let Some(position) = channels.iter().position(|channel| channel.id == id) else {
+                return Err(format!("You are not subscribed to '{}'", id));
+            };
+
+            let channel = channels.remove(position);
+            println!("Unsubscribed from {} ({})", channel.name, channel.id);
+            cache::cache_channels(&channels).map_err(|err| err.to_string())
+        }
+        Command::Play(id) => {
+            let existing = channels.iter_mut().find_map(|channel| {
+                let info = ChannelInfo::from(&*channel);
+                channel
+                    .videos
+                    .iter_mut()
+                    .find(|video| video.id == id)
+                    .map(|video| (info, video))
+            });
+
+            let before = progress.get(&id);
+            let url = format!("{}{}", "https://www.youtube.com/watch?v=", id);
+            let Some(after) =
+                player_view::play_headless(&url, before.as_ref()).map_err(play_error)?
+            else {
+                return Ok(());
+            };
+
+            // The video has already played, so failing to save afterwards is only reported
+            progress.set(&id, after);
+            if let Err(err) = progress.save() {
+                warn(format!("Could not save watch progress.\nError: {:?}", err));
+            }
+
+            // Not from a subscription, so there is no channel to record in history or cache
+            let Some((info, video)) = existing else {
+                return Ok(());
+            };
+
+            let start = before.map(|before| before.current).unwrap_or(0);
+            let entry = HistoryEntry::new(&video.id, &video.title, &info, start, after.current);
+            if let Err(err) = history::record(&entry) {
+                warn(format!("Could not save watch history.\nError: {:?}", err));
+            }
+
+            video.set_progress(Some(after), config.watched_percent);
+            video.seen = true;
+
+            let channel = channels.channel_by_id(&info.id).unwrap();
+            let root = cache::data_directory().map_err(|err| err.to_string())?;
+            cache::cache_videos(&root, &channel.id, &channel.videos).map_err(|err| err.to_string())
+        }
+        Command::Import(path) => {
+            let report = importers::import(&path, &channels, config).map_err(|err| {
+                format!(
+                    "Could not import subscriptions from '{}'. Error: {}",
+                    path.display(),
+                    err
+                )
+            })?;
+
+            println!(
+                "{} Import: {} Subscribed, {} Skipped, {} Failed",
+                report.format.name(),
+                report.channels.len(),
+                report.skipped.len(),
+                report.failed.len()
+            );
+            report
+                .channels
+                .iter()
+                .for_each(|channel| println!("+ {}", channel.name));
+            report
+                .skipped
+                .iter()
+                .for_each(|issue| println!("- {} ({})", issue.name, issue.reason));
+            report
+                .failed
+                .iter()
+                .for_each(|issue| println!("! {} ({})", issue.name, issue.reason));
+
+            if !report.channels.is_empty() {
+                report
+                    .channels
+                    .into_iter()
+                    .for_each(|channel| channels.subscribe(channel, &progress, &filters));
+                cache::cache_channels(&channels).map_err(|err| err.to_string())?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn parse_tab(name: &str) -> Result<ChannelTab, String> {
+    ChannelTab::ALL
+        .into_iter()
+        .find(|tab| tab.path() == name)
+        .ok_or(USAGE.to_owned())
+}
+
+/// Reports a failure both on stderr and in the log, for failures that should not fail the command
+fn warn(message: String) {
+    eprintln!("{}", message);
+    log::err(message);
+}
+
+fn play_error(err: Error) -> String {
+    format!("Could not run play command: mpv.\nError: {}", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<Option<Command>, String> {
+        parse(
+            &args
+                .split_whitespace()
+                .map(str::to_owned)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn flags_can_come_before_or_after_arguments() {
+        for args in [
+            "list channel --json UCabc",
+            "list --json channel UCabc",
+            "list channel UCabc --json",
+        ] {
+            let Ok(Some(Command::List {
+                listing: Listing::Channel(id, ChannelTab::Videos),
+                json: true,
+                unwatched: false,
+                hidden: false,
+            })) = parse_args(args)
+            else {
+                panic!("'{}' was not parsed as a JSON channel listing", args);
+            };
+            assert_eq!(id, "UCabc");
+        }
+
+        assert!(matches!(
+            parse_args("list channel --hidden UCabc shorts"),
+            Ok(Some(Command::List {
+                listing: Listing::Channel(_, ChannelTab::Shorts),
+                hidden: true,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn video_ids_starting_with_dashes_are_not_flags() {
+        let Ok(Some(Command::Play(id))) = parse_args("play --a1b2c3d4e") else {
+            panic!("the video ID was not parsed");
+        };
+        assert_eq!(id, "--a1b2c3d4e");
+    }
+
+    #[test]
+    fn missing_or_extra_arguments_are_errors() {
+        assert!(parse_args("").unwrap().is_none());
+        assert!(parse_args("list channel --json").is_err());
+        assert!(parse_args("list channel UCabc videos extra").is_err());
+        assert!(parse_args("list channel UCabc live").is_err());
+        assert!(parse_args("refresh now").is_err());
+        assert!(parse_args("--json").is_err());
+    }
+}
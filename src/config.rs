@@ -5,16 +5,71 @@ use std::{
     path::Path,
 };
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
-use crate::view::Error;
+use crate::{
+    filters::FilterConfig,
+    sources::{self, SourceKind, VideoSource},
+    view::Error,
+    yt::ChannelTab,
+};
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub videos_per_channel: usize,
     pub videos_per_search: usize,
     pub saved_video_path: String,
     pub refresh_on_start: bool,
+    pub refresh_workers: usize,
+    pub refresh_timeout: u64,
+    pub auto_refresh_minutes: u64,
+    pub watched_percent: u32,
+    pub hide_watched: bool,
+    pub streams_tab: bool,
+    pub shorts_tab: bool,
+    pub continue_after_seconds: u32,
+    pub continue_until_percent: u32,
+    pub source: SourceKind,
+    pub rss_url: String,
+    pub invidious_url: String,
+    pub fixture_directory: String,
+    // Tables must come after every plain value for the default config to serialize
+    pub filters: FilterConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            videos_per_channel: 60,
+            videos_per_search: 60,
+            saved_video_path: format!(
+                "{}{}",
+                dirs::video_dir()
+                    .or_else(|| dirs::home_dir().map(|home| home.join("Videos")))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path::MAIN_SEPARATOR
+            ),
+            refresh_on_start: false,
+            refresh_workers: 4,
+            refresh_timeout: 60,
+            auto_refresh_minutes: 0,
+            watched_percent: 90,
+            hide_watched: false,
+            streams_tab: false,
+            shorts_tab: false,
+            continue_after_seconds: 30,
+            continue_until_percent: 90,
+            source: SourceKind::YtDlp,
+            rss_url: "https://www.youtube.com".to_owned(),
+            invidious_url: String::new(),
+            fixture_directory: String::new(),
+            filters: FilterConfig::default(),
+        }
+    }
 }
 
 impl Config {
@@ -32,19 +87,7 @@ impl Config {
         let file = root.join("config.toml");
 
         if !Path::exists(&file) {
-            let default_config = Config {
-                videos_per_channel: 60,
-                videos_per_search: 60,
-                saved_video_path: format!(
-                    "{}{}",
-                    dirs::video_dir()
-                        .or_else(|| dirs::home_dir().map(|home| home.join("Videos")))
-                        .map(|path| path.to_string_lossy().to_string())
-                        .ok_or(Error::FileBadAccess)?,
-                    path::MAIN_SEPARATOR
-                ),
-                refresh_on_start: false,
-            };
+            let default_config = Config::default();
             let toml = toml::to_string(&default_config).map_err(|_| Error::TomlParsing)?;
             fs::write(file, toml).map_err(|_| Error::TomlParsing)?;
 
@@ -59,9 +102,23 @@ impl Config {
 
                 toml::from_str(&raw).map_err(|_| Error::TomlParsing)
             }
-            Err(_) => {
-                Err(Error::FileBadAccess)
-            }
+            Err(_) => Err(Error::FileBadAccess),
         }
     }
+
+    pub fn source(&self) -> Arc<dyn VideoSource> {
+        sources::from_config(self)
+    }
+
+    /// The channel tabs to fetch and show, always starting with the videos tab
+    pub fn channel_tabs(&self) -> Vec<ChannelTab> {
+        ChannelTab::ALL
+            .into_iter()
+            .filter(|tab| match tab {
+                ChannelTab::Videos => true,
+                ChannelTab::Streams => self.streams_tab,
+                ChannelTab::Shorts => self.shorts_tab,
+            })
+            .collect()
+    }
 }
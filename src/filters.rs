@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    log,
+    yt::{Channel, Channels, Video, VideoWatchLater},
+};
+
+/// The `[filters]` table of `config.toml`
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// Hides videos whose titles contain any of these, ignoring case
+    pub muted_keywords: Vec<String>,
+    /// Hides videos whose titles match any of these regular expressions
+    pub muted_patterns: Vec<String>,
+    /// 0 disables the limit
+    pub min_duration_seconds: u32,
+    /// 0 disables the limit
+    pub max_duration_seconds: u32,
+    /// Channel IDs left out of the subscription feed, while still shown everywhere else
+    pub mixed_feed_excluded_channels: Vec<String>,
+}
+
+/// Filter rules ready to check videos against
+#[derive(Default)]
+pub struct Filters {
+    keywords: Vec<String>,
+    patterns: Vec<Regex>,
+    min_duration: u32,
+    max_duration: u32,
+    excluded_channels: HashSet<String>,
+}
+
+impl Filters {
+    /// Invalid patterns are logged and skipped so the rest still apply
+    pub fn new(config: &FilterConfig) -> Filters {
+        Filters {
+            keywords: config
+                .muted_keywords
+                .iter()
+                .filter(|keyword| !keyword.is_empty())
+                .map(|keyword| keyword.to_lowercase())
+                .collect(),
+            patterns: config
+                .muted_patterns
+                .iter()
+                .filter_map(|pattern| {
+                    Regex::new(pattern)
+                        .map_err(|err| {
+                            log::err(format!(
+                                "Could not parse muted pattern: '{}'.\nError: {}",
+                                pattern, err
+                            ))
+                        })
+                        .ok()
+                })
+                .collect(),
+            min_duration: config.min_duration_seconds,
+            max_duration: config.max_duration_seconds,
+            excluded_channels: config
+                .mixed_feed_excluded_channels
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether a video is muted by its title or length. Videos of unknown length are never hidden for it
+    pub fn hides(&self, title: &str, duration: Option<u32>) -> bool {
+        let lowercase = title.to_lowercase();
+
+        self.keywords
+            .iter()
+            .any(|keyword| lowercase.contains(keyword))
+            || self.patterns.iter().any(|pattern| pattern.is_match(title))
+            || duration.is_some_and(|duration| {
+                (self.min_duration > 0 && duration < self.min_duration)
+                    || (self.max_duration > 0 && duration > self.max_duration)
+            })
+    }
+
+    /// Marks every muted video and excluded channel as they are loaded, so views only need to check the flags.
+    /// Videos fetched afterwards are marked as they are added to their channel
+    pub fn apply(&self, channels: &mut Channels, watch_later: &mut [VideoWatchLater]) {
+        channels
+            .iter_mut()
+            .for_each(|channel| self.apply_channel(channel));
+        watch_later
+            .iter_mut()
+            .for_each(|later| self.mark(&mut later.video));
+    }
+
+    /// Marks a channel's videos, and whether it is left out of the subscription feed
+    pub fn apply_channel(&self, channel: &mut Channel) {
+        channel.excluded_from_mixed = self.excluded_channels.contains(&channel.id);
+        channel
+            .videos
+            .iter_mut()
+            .chain(channel.upcoming.iter_mut())
+            .for_each(|video| self.mark(video));
+    }
+
+    pub fn mark(&self, video: &mut Video) {
+        video.hidden = self.hides(&video.title, video.duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local};
+
+    use super::*;
+    use crate::{progress::ProgressStore, yt::ChannelInfo};
+
+    fn video(title: &str, duration: Option<u32>) -> Video {
+        Video {
+            duration,
+            ..Video::new(title, title, "N/A", DateTime::<Local>::MIN_UTC.into())
+        }
+    }
+
+    fn filters(config: FilterConfig) -> Filters {
+        Filters::new(&config)
+    }
+
+    #[test]
+    fn keywords_and_patterns_mute_titles() {
+        let filters = filters(FilterConfig {
+            muted_keywords: vec!["Giveaway".to_owned(), String::new()],
+            muted_patterns: vec![r"(?i)^#shorts".to_owned(), "(unclosed".to_owned()],
+            ..FilterConfig::default()
+        });
+
+        assert!(filters.hides("Huge GIVEAWAY today", None));
+        assert!(filters.hides("#Shorts cat compilation", None));
+        assert!(!filters.hides("Cat compilation #shorts", None));
+        // The empty keyword and invalid pattern are skipped rather than muting everything
+        assert!(!filters.hides("A normal video", None));
+    }
+
+    #[test]
+    fn durations_outside_the_limits_are_muted() {
+        let filters = filters(FilterConfig {
+            min_duration_seconds: 60,
+            max_duration_seconds: 3600,
+            ..FilterConfig::default()
+        });
+
+        assert!(filters.hides("Short", Some(59)));
+        assert!(!filters.hides("Just long enough", Some(60)));
+        assert!(filters.hides("Stream", Some(3601)));
+        assert!(!filters.hides("Unknown length", None));
+        assert!(!Filters::default().hides("No limits", Some(1)));
+    }
+
+    #[test]
+    fn loaded_and_added_videos_are_marked() {
+        let filters = filters(FilterConfig {
+            muted_keywords: vec!["sponsored".to_owned()],
+            mixed_feed_excluded_channels: vec!["UCnews".to_owned()],
+            ..FilterConfig::default()
+        });
+        let info = |id: &str| ChannelInfo {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            kind: Default::default(),
+        };
+
+        let mut channels = Channels(vec![
+            Channel::from_info(&info("UCnews"), vec![video("Sponsored segment", None)]),
+            Channel::from_info(&info("UCmusic"), vec![video("New single", None)]),
+        ]);
+        let mut watch_later = vec![VideoWatchLater {
+            video: video("Sponsored review", None),
+            channel: info("UCmusic"),
+        }];
+        filters.apply(&mut channels, &mut watch_later);
+
+        assert!(channels[0].excluded_from_mixed && channels[0].videos[0].hidden);
+        assert!(!channels[1].excluded_from_mixed && !channels[1].videos[0].hidden);
+        assert!(watch_later[0].video.hidden);
+
+        channels[1].add_videos(
+            vec![
+                video("Sponsored unboxing", None),
+                video("Live session", None),
+            ],
+            &ProgressStore::default(),
+            &filters,
+        );
+        let hidden = |title: &str| {
+            channels[1]
+                .videos
+                .iter()
+                .find(|video| video.title == title)
+                .unwrap()
+                .hidden
+        };
+        assert!(hidden("Sponsored unboxing"));
+        assert!(!hidden("Live session"));
+    }
+}
@@ -1,9 +1,46 @@
-use crate::{page::Page, views::View};
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::{
+    history::HistoryEntry,
+    page::Page,
+    views::View,
+    yt::{Video, VideoWatchLater},
+};
 
 pub struct Finder<T> {
     elements: Vec<T>,
     page: Page,
-    query: Option<String>,
+    query: Option<Query>,
+}
+
+/// What a find query can match on. Elements without a field never match that field's prefix
+pub trait Findable {
+    fn title(&self) -> &str;
+    fn channel(&self) -> Option<&str>;
+    fn date(&self) -> DateTime<Local>;
+    /// The descriptions and watched status of videos can be searched too
+    fn video(&self) -> Option<&Video> {
+        None
+    }
+}
+
+/// A parsed find query. Plain words are fuzzy matched against titles, or channels when a title does not match,
+/// and `channel:`, `desc:`, `before:`, `after:` and `watched:` prefixes narrow the results
+struct Query {
+    input: String,
+    terms: String,
+    channel: Option<String>,
+    description: Option<String>,
+    before: Option<NaiveDate>,
+    after: Option<NaiveDate>,
+    watched: Option<bool>,
+}
+
+/// Characters matched by a fuzzy search and how well they matched
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices into the searched text
+    pub indices: Vec<usize>,
 }
 
 impl<T> Finder<T> {
@@ -39,19 +76,333 @@ impl<T> Finder<T> {
         }
     }
 
+    /// The query as it was typed
     pub fn query(&self) -> Option<&str> {
-        self.query.as_deref()
+        self.query.as_ref().map(|query| query.input.as_str())
+    }
+
+    /// The fuzzy matched words of the query without any field prefixes, for highlighting
+    pub fn terms(&self) -> Option<&str> {
+        self.query.as_ref().map(|query| query.terms.as_str())
     }
 
     pub fn reset(&mut self, view: &mut View) {
         view.update_filter(None);
         self.query = None;
     }
+}
 
-    pub fn update(&mut self, view: &mut View, elements: Vec<T>, new_query: &str) {
-        self.query = Some(new_query.to_owned());
-        self.elements = elements;
+impl<T: Findable + Clone> Finder<T> {
+    /// Keeps the elements matching the query, best matches first. Returns an error for badly formed fields
+    pub fn search(&mut self, view: &mut View, elements: &[T], input: &str) -> Result<(), String> {
+        let query = Query::parse(input)?;
+
+        let mut matches = elements
+            .iter()
+            .filter_map(|element| query.score(element).map(|score| (score, element)))
+            .collect::<Vec<(i64, &T)>>();
+        // Stable, so equally good matches keep their order
+        matches.sort_by_key(|(score, _)| -score);
+
+        self.elements = matches
+            .into_iter()
+            .map(|(_, element)| element.clone())
+            .collect();
         self.page = Page::new(self.elements.len(), self.page.lines_per_element);
-        view.update_filter(Some(new_query.to_owned()));
+        view.update_filter(Some(input.to_owned()));
+        self.query = Some(query);
+        Ok(())
+    }
+}
+
+impl Query {
+    fn parse(input: &str) -> Result<Query, String> {
+        let mut query = Query {
+            input: input.to_owned(),
+            terms: String::new(),
+            channel: None,
+            description: None,
+            before: None,
+            after: None,
+            watched: None,
+        };
+        let mut terms = Vec::new();
+
+        for word in input.split_whitespace() {
+            let Some((field, value)) = word.split_once(':') else {
+                terms.push(word);
+                continue;
+            };
+
+            match field.to_lowercase().as_str() {
+                "channel" => query.channel = Some(value.to_lowercase()),
+                "desc" => query.description = Some(value.to_lowercase()),
+                "before" => query.before = Some(parse_date(value)?),
+                "after" => query.after = Some(parse_date(value)?),
+                "watched" => {
+                    query.watched = match value.to_lowercase().as_str() {
+                        "yes" | "true" => Some(true),
+                        "no" | "false" => Some(false),
+                        _ => return Err(format!("'{}' is not yes or no!", value)),
+                    }
+                }
+                _ => terms.push(word),
+            }
+        }
+
+        query.terms = terms.join(" ");
+        Ok(query)
+    }
+
+    /// How well an element matches, or None if it does not match at all
+    fn score(&self, element: &impl Findable) -> Option<i64> {
+        let contains = |field: Option<&str>, value: &Option<String>| match value {
+            Some(value) => field.is_some_and(|field| field.to_lowercase().contains(value)),
+            None => true,
+        };
+
+        let video = element.video();
+        let date = element.date().date_naive();
+
+        let fields_match = contains(element.channel(), &self.channel)
+            && contains(
+                video.map(|video| video.description.as_str()),
+                &self.description,
+            )
+            && self.before.is_none_or(|before| date < before)
+            && self.after.is_none_or(|after| date >= after)
+            && self
+                .watched
+                .is_none_or(|watched| video.is_some_and(|video| video.watched == watched));
+
+        if !fields_match {
+            return None;
+        }
+
+        // Every word has to match, with matches in the title ranked above those in the channel
+        self.terms.split_whitespace().try_fold(0, |total, term| {
+            let score = fuzzy_match(element.title(), term)
+                .map(|found| found.score)
+                .or_else(|| {
+                    element
+                        .channel()
+                        .and_then(|channel| fuzzy_match(channel, term))
+                        .map(|found| found.score / 2)
+                })?;
+            Some(total + score)
+        })
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("'{}' is not a date like 2024-12-31!", value))
+}
+
+/// Matches the pattern's characters in order, ignoring case. Exact runs, word starts and early matches score highest.
+/// Characters that are not part of an exact run have to start a word or follow the character matched before them,
+/// so short patterns do not match nearly everything by picking letters from anywhere
+pub fn fuzzy_match(text: &str, pattern: &str) -> Option<FuzzyMatch> {
+    let text = text.chars().collect::<Vec<char>>();
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let word_start = |index: usize| index == 0 || !text[index - 1].is_alphanumeric();
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+
+    // An exact run is always the best match, so prefer it over scattered characters
+    if let Some(start) = text
+        .windows(pattern.len())
+        .position(|window| window.iter().zip(pattern.iter()).all(|(a, b)| same(*a, *b)))
+    {
+        return Some(FuzzyMatch {
+            score: 100 + pattern.len() as i64 * 16 - start as i64,
+            indices: (start..start + pattern.len()).collect(),
+        });
+    }
+
+    let mut indices: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+
+    for character in pattern {
+        let follows = indices
+            .last()
+            .map(|last| last + 1)
+            .filter(|index| *index < text.len() && same(text[*index], character));
+
+        let index = match follows {
+            Some(index) => {
+                score += 16;
+                index
+            }
+            None => {
+                let index = (next..text.len())
+                    .find(|index| word_start(*index) && same(text[*index], character))?;
+                score += 8;
+                index
+            }
+        };
+        score -= (index - next) as i64;
+
+        indices.push(index);
+        next = index + 1;
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+impl Findable for VideoWatchLater {
+    fn title(&self) -> &str {
+        &self.video.title
+    }
+
+    fn channel(&self) -> Option<&str> {
+        Some(&self.channel.name)
+    }
+
+    fn date(&self) -> DateTime<Local> {
+        self.video.upload
+    }
+
+    fn video(&self) -> Option<&Video> {
+        Some(&self.video)
+    }
+}
+
+impl Findable for HistoryEntry {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn channel(&self) -> Option<&str> {
+        Some(&self.channel_name)
+    }
+
+    /// When it was watched, as history has no upload dates
+    fn date(&self) -> DateTime<Local> {
+        self.watched_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    struct Element {
+        title: &'static str,
+        channel: &'static str,
+        date: DateTime<Local>,
+    }
+
+    impl Findable for Element {
+        fn title(&self) -> &str {
+            self.title
+        }
+
+        fn channel(&self) -> Option<&str> {
+            Some(self.channel)
+        }
+
+        fn date(&self) -> DateTime<Local> {
+            self.date
+        }
+    }
+
+    fn element(title: &'static str, channel: &'static str) -> Element {
+        Element {
+            title,
+            channel,
+            date: Local.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap(),
+        }
+    }
+
+    fn ranked<'a>(query: &str, elements: &'a [Element]) -> Vec<&'a str> {
+        let query = Query::parse(query).unwrap();
+        let mut matches = elements
+            .iter()
+            .filter_map(|element| query.score(element).map(|score| (score, element.title)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(score, _)| -score);
+        matches.into_iter().map(|(_, title)| title).collect()
+    }
+
+    #[test]
+    fn exact_runs_rank_above_word_starts_and_channels() {
+        let elements = [
+            element("Building a Rust Parser", "Tech"),
+            element("Rust in 100 Seconds", "Tech"),
+            element("Cooking Pasta", "Rusty Pans"),
+            element("Road Under Stars Tonight", "Travel"),
+            element("Frustrating Bugs", "Tech"),
+            element("Our Best Tutorial", "Tech"),
+        ];
+
+        assert_eq!(
+            ranked("rust", &elements),
+            vec![
+                "Rust in 100 Seconds",
+                "Frustrating Bugs",
+                "Building a Rust Parser",
+                "Cooking Pasta",
+                "Road Under Stars Tonight",
+            ]
+        );
+        assert_eq!(
+            ranked("rust seconds", &elements),
+            vec!["Rust in 100 Seconds"]
+        );
+    }
+
+    #[test]
+    fn scattered_characters_must_start_words() {
+        let found = fuzzy_match("Linus Tech Tips", "ltt").unwrap();
+        assert_eq!(found.indices, vec![0, 6, 11]);
+
+        let found = fuzzy_match("Linus Tech Tips", "tetip").unwrap();
+        assert_eq!(found.indices, vec![6, 7, 11, 12, 13]);
+
+        assert!(fuzzy_match("Linus Tech Tips", "lns").is_none());
+        assert!(fuzzy_match("Chemistry Explained", "cy").is_none());
+        assert!(fuzzy_match("Chemistry Explained", "ce").is_some());
+    }
+
+    #[test]
+    fn prefixes_narrow_results() {
+        let query = Query::parse("rust channel:Tech before:2024-07-01 after:2024-06-01").unwrap();
+        assert_eq!(query.terms, "rust");
+        assert_eq!(query.channel.as_deref(), Some("tech"));
+        assert_eq!(query.before, NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert_eq!(query.after, NaiveDate::from_ymd_opt(2024, 6, 1));
+        assert!(query
+            .score(&element("Rust in 100 Seconds", "Tech"))
+            .is_some());
+        assert!(query
+            .score(&element("Rust in 100 Seconds", "Music"))
+            .is_none());
+
+        let query = Query::parse("after:2024-06-16").unwrap();
+        assert!(query
+            .score(&element("Rust in 100 Seconds", "Tech"))
+            .is_none());
+
+        // Unknown prefixes are searched for like any other word
+        let query = Query::parse("re:zero").unwrap();
+        assert_eq!(query.terms, "re:zero");
+    }
+
+    #[test]
+    fn badly_formed_prefixes_are_errors() {
+        assert!(Query::parse("before:yesterday").is_err());
+        assert!(Query::parse("after:2024-13-01").is_err());
+        assert!(Query::parse("watched:maybe").is_err());
+        assert_eq!(Query::parse("watched:NO").unwrap().watched, Some(false));
     }
 }
@@ -0,0 +1,164 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    ops::Deref,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cache::data_directory, log, view::Error};
+
+/// A named set of channels shown together on the home view
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Group {
+    pub name: String,
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+/// Every channel group, saved in `groups.json` next to `channels.json`. A channel is in at most one group
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Groups(Vec<Group>);
+
+impl Groups {
+    pub fn load() -> Groups {
+        let Ok(path) = data_directory().map(|root| root.join("groups.json")) else {
+            return Groups::default();
+        };
+
+        if !Path::exists(&path) {
+            return Groups::default();
+        }
+
+        File::open(&path)
+            .ok()
+            .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+            .unwrap_or_else(|| {
+                log::err(format!("Could not load json for {:?}\n", path));
+                Groups::default()
+            })
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let path = data_directory()?.join("groups.json");
+        let file = File::create(path).map_err(|_| Error::FileBadAccess)?;
+        serde_json::to_writer(BufWriter::new(file), self).map_err(|_| Error::JsonParsing)
+    }
+
+    /// Group names are matched ignoring case everywhere, so "tech" and "Tech" are the same group
+    fn position(&self, name: &str) -> Option<usize> {
+        self.iter()
+            .position(|group| group.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.position(name).map(|index| &self.0[index])
+    }
+
+    pub fn group_of(&self, channel_id: &str) -> Option<&Group> {
+        self.iter()
+            .find(|group| group.channel_ids.iter().any(|id| id == channel_id))
+    }
+
+    /// Moves a channel into the named group, creating it if needed, or out of every group when no name is given.
+    /// Groups left empty are removed
+    pub fn assign(&mut self, channel_id: &str, name: Option<&str>) {
+        self.0
+            .iter_mut()
+            .for_each(|group| group.channel_ids.retain(|id| id != channel_id));
+
+        if let Some(name) = name {
+            match self.position(name) {
+                Some(index) => self.0[index].channel_ids.push(channel_id.to_owned()),
+                None => {
+                    self.0.push(Group {
+                        name: name.trim().to_owned(),
+                        channel_ids: vec![channel_id.to_owned()],
+                        collapsed: false,
+                    });
+                    self.0.sort_by_key(|group| group.name.to_lowercase());
+                }
+            }
+        }
+
+        self.0.retain(|group| !group.channel_ids.is_empty());
+    }
+
+    pub fn toggle_collapsed(&mut self, name: &str) {
+        if let Some(index) = self.position(name) {
+            self.0[index].collapsed = !self.0[index].collapsed;
+        }
+    }
+}
+
+impl Deref for Groups {
+    type Target = Vec<Group>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(groups: &Groups, name: &str) -> Option<Vec<String>> {
+        groups.group(name).map(|group| group.channel_ids.clone())
+    }
+
+    #[test]
+    fn names_match_ignoring_case_everywhere() {
+        let mut groups = Groups::default();
+        groups.assign("UCfirst", Some("Tech"));
+        groups.assign("UCsecond", Some(" tech "));
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "Tech");
+        assert_eq!(
+            members(&groups, "TECH"),
+            Some(vec!["UCfirst".to_owned(), "UCsecond".to_owned()])
+        );
+
+        groups.toggle_collapsed("tech");
+        assert!(groups[0].collapsed);
+    }
+
+    #[test]
+    fn reassigning_moves_a_channel_between_groups() {
+        let mut groups = Groups::default();
+        groups.assign("UCfirst", Some("Tech"));
+        groups.assign("UCsecond", Some("Tech"));
+        groups.assign("UCfirst", Some("Music"));
+
+        assert_eq!(members(&groups, "Music"), Some(vec!["UCfirst".to_owned()]));
+        assert_eq!(members(&groups, "Tech"), Some(vec!["UCsecond".to_owned()]));
+        assert_eq!(groups.group_of("UCfirst").unwrap().name, "Music");
+        // Kept in order by name
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Music", "Tech"]
+        );
+    }
+
+    #[test]
+    fn groups_left_empty_are_removed() {
+        let mut groups = Groups::default();
+        groups.assign("UCfirst", Some("Tech"));
+        groups.assign("UCsecond", Some("Music"));
+
+        groups.assign("UCfirst", None);
+        assert!(groups.group("Tech").is_none());
+        assert!(groups.group_of("UCfirst").is_none());
+
+        groups.assign("UCsecond", Some("Tech"));
+        assert!(groups.group("Music").is_none());
+        assert_eq!(groups.len(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{cache::data_directory, view::Error, yt::ChannelInfo};
+
+/// One playback of a video, appended to `history.jsonl` each time a video is played
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub video_id: String,
+    pub title: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub watched_at: DateTime<Local>,
+    pub start_seconds: u32,
+    pub end_seconds: u32,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        video_id: &str,
+        title: &str,
+        channel: &ChannelInfo,
+        start_seconds: u32,
+        end_seconds: u32,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            video_id: video_id.to_owned(),
+            title: title.to_owned(),
+            channel_id: channel.id.clone(),
+            channel_name: channel.name.clone(),
+            watched_at: Local::now(),
+            start_seconds,
+            end_seconds,
+        }
+    }
+}
+
+pub fn record(entry: &HistoryEntry) -> Result<(), Error> {
+    let path = data_directory()?.join("history.jsonl");
+    let line = serde_json::to_string(entry).map_err(|_| Error::JsonParsing)?;
+
+    File::options()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|_| Error::FileBadAccess)
+}
+
+/// Every recorded playback, most recent first. Lines that can not be parsed are skipped
+pub fn load() -> Vec<HistoryEntry> {
+    let Ok(file) = data_directory()
+        .and_then(|root| File::open(root.join("history.jsonl")).map_err(|_| Error::FileBadAccess))
+    else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<HistoryEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+
+    entries.reverse();
+    entries
+}
+
+pub fn clear() -> Result<(), Error> {
+    let path = data_directory()?.join("history.jsonl");
+
+    if Path::exists(&path) {
+        fs::remove_file(path).map_err(|_| Error::FileBadAccess)?;
+    }
+    Ok(())
+}
+
+pub fn export(path: &Path, entries: &[HistoryEntry]) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(entries).map_err(|_| Error::JsonParsing)?;
+    fs::write(path, json).map_err(|_| Error::FileBadAccess)
+}
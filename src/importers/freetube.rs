@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::view::Error;
+
+use super::ImportEntry;
+
+#[derive(Deserialize)]
+struct FreeTubeProfile {
+    #[serde(rename = "_id")]
+    id: String,
+    subscriptions: Vec<FreeTubeSubscription>,
+}
+
+#[derive(Deserialize)]
+struct FreeTubeSubscription {
+    id: String,
+    name: String,
+}
+
+/// Parses a FreeTube `profiles.db`, which stores one JSON document per line (NeDB)
+pub fn parse(raw: &str) -> Result<Vec<ImportEntry>, Error> {
+    let mut profiles: Vec<FreeTubeProfile> = Vec::new();
+
+    for line in raw.lines().filter(|line| !line.trim().is_empty()) {
+        let json: Value = serde_json::from_str(line).map_err(|_| Error::JsonParsing)?;
+
+        // NeDB appends updates and deletions, so later documents replace earlier ones
+        let id = json["_id"].as_str().unwrap_or_default().to_owned();
+        profiles.retain(|profile| profile.id != id);
+
+        if json.get("$$deleted").is_some() {
+            continue;
+        }
+
+        if let Ok(profile) = serde_json::from_value(json) {
+            profiles.push(profile);
+        }
+    }
+
+    if profiles.is_empty() {
+        return Err(Error::JsonParsing);
+    }
+
+    Ok(profiles
+        .into_iter()
+        .flat_map(|profile| profile.subscriptions)
+        .map(|subscription| {
+            ImportEntry::new(
+                subscription.name,
+                format!("https://www.youtube.com/channel/{}", subscription.id),
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,388 @@
+use std::{fs, path::Path, thread};
+
+use itertools::Itertools;
+
+use crate::{
+    config::Config,
+    sources::VideoSource,
+    updates::fetch_updates,
+    view::Error,
+    yt::{
+        channel_id_from_url, playlist_id_from_url, Channel, ChannelInfo, Channels, SubscriptionKind,
+    },
+};
+
+pub mod freetube;
+pub mod newpipe;
+pub mod opml;
+pub mod takeout;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportFormat {
+    Opml,
+    Takeout,
+    NewPipe,
+    FreeTube,
+}
+
+pub struct ImportEntry {
+    pub name: String,
+    pub url: String,
+    pub unsupported: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportIssue {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportReport {
+    pub format: ImportFormat,
+    pub channels: Vec<Channel>,
+    pub skipped: Vec<ImportIssue>,
+    pub failed: Vec<ImportIssue>,
+}
+
+enum Resolved {
+    Channel(ChannelInfo),
+    Skipped(ImportIssue),
+    Failed(ImportIssue),
+}
+
+impl ImportFormat {
+    pub fn detect(path: &Path, raw: &str) -> ImportFormat {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+
+        match extension.as_deref() {
+            Some("opml") | Some("xml") => ImportFormat::Opml,
+            Some("csv") => ImportFormat::Takeout,
+            Some("db") => ImportFormat::FreeTube,
+            Some("json") => ImportFormat::NewPipe,
+            _ => {
+                let raw = raw.trim_start();
+                if raw.starts_with('<') {
+                    ImportFormat::Opml
+                } else if raw.starts_with('{') && raw.lines().count() > 1 {
+                    ImportFormat::FreeTube
+                } else if raw.starts_with('{') {
+                    ImportFormat::NewPipe
+                } else {
+                    ImportFormat::Takeout
+                }
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::Opml => "OPML",
+            ImportFormat::Takeout => "Google Takeout",
+            ImportFormat::NewPipe => "NewPipe",
+            ImportFormat::FreeTube => "FreeTube",
+        }
+    }
+}
+
+impl ImportEntry {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> ImportEntry {
+        ImportEntry {
+            name: name.into(),
+            url: url.into(),
+            unsupported: false,
+        }
+    }
+
+    fn issue(&self, reason: impl Into<String>) -> ImportIssue {
+        ImportIssue {
+            name: self.name.clone(),
+            reason: reason.into(),
+        }
+    }
+
+    fn resolve(&self, source: &dyn VideoSource) -> Resolved {
+        if self.unsupported {
+            return Resolved::Skipped(self.issue("Not a YouTube subscription"));
+        }
+
+        if let Some(id) = channel_id_from_url(&self.url) {
+            return Resolved::Channel(ChannelInfo {
+                id,
+                name: self.name.clone(),
+                kind: SubscriptionKind::Channel,
+            });
+        }
+
+        // Playlists exported by yt-feeds keep their playlist feed URL
+        if let Some(id) = playlist_id_from_url(&self.url) {
+            return Resolved::Channel(ChannelInfo {
+                id,
+                name: self.name.clone(),
+                kind: SubscriptionKind::Playlist,
+            });
+        }
+
+        if !self.url.contains("youtube.com/") {
+            return Resolved::Skipped(self.issue("Not a YouTube channel"));
+        }
+
+        // Handles and legacy user URLs have to be looked up to find their channel ID
+        match source.fetch_channel_from_url(&self.url) {
+            Ok(channel) => Resolved::Channel(ChannelInfo {
+                name: self.name.clone(),
+                ..channel
+            }),
+            Err(err) => Resolved::Failed(
+                self.issue(format!("Could not resolve '{}'. Error: {}", self.url, err)),
+            ),
+        }
+    }
+}
+
+pub fn parse(path: &Path) -> Result<(ImportFormat, Vec<ImportEntry>), Error> {
+    let raw = fs::read_to_string(path).map_err(|_| Error::FileBadAccess)?;
+    let format = ImportFormat::detect(path, &raw);
+
+    let entries = match format {
+        ImportFormat::Opml => opml::parse(&raw)?,
+        ImportFormat::Takeout => takeout::parse(&raw),
+        ImportFormat::NewPipe => newpipe::parse(&raw)?,
+        ImportFormat::FreeTube => freetube::parse(&raw)?,
+    };
+
+    Ok((format, entries))
+}
+
+/// Parses, resolves and downloads the feeds of every channel in an export that is not already subscribed
+pub fn import(path: &Path, channels: &Channels, config: &Config) -> Result<ImportReport, Error> {
+    let (format, entries) = parse(path)?;
+    let source = config.source();
+
+    let mut report = ImportReport {
+        format,
+        channels: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    // Resolve in batches no larger than the refresh pool
+    let resolved = entries
+        .chunks(config.refresh_workers.max(1))
+        .flat_map(|batch| {
+            thread::scope(|scope| {
+                batch
+                    .iter()
+                    .map(|entry| scope.spawn(|| entry.resolve(source.as_ref())))
+                    .collect_vec()
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .collect_vec()
+            })
+        })
+        .collect_vec();
+
+    let mut new_channels: Vec<ChannelInfo> = Vec::new();
+
+    for resolved in resolved {
+        match resolved {
+            Resolved::Channel(channel) => {
+                if channels.has_channel(&channel.id) {
+                    report.skipped.push(ImportIssue {
+                        name: channel.name,
+                        reason: "Already subscribed".to_owned(),
+                    });
+                } else if new_channels.iter().any(|new| new.id == channel.id) {
+                    report.skipped.push(ImportIssue {
+                        name: channel.name,
+                        reason: "Duplicate entry".to_owned(),
+                    });
+                } else {
+                    new_channels.push(channel);
+                }
+            }
+            Resolved::Skipped(issue) => report.skipped.push(issue),
+            Resolved::Failed(issue) => report.failed.push(issue),
+        }
+    }
+
+    let mut refresh = fetch_updates(
+        source,
+        new_channels
+            .into_iter()
+            .map(|channel| channel.into())
+            .collect(),
+        &config.channel_tabs(),
+        config.videos_per_channel,
+        config.refresh_workers,
+    );
+
+    while let Some(update) = refresh.next_update(true) {
+        match update.result {
+            Ok(feed) => report
+                .channels
+                .push(Channel::from_info(&update.channel, feed)),
+            Err(err) => report.failed.push(ImportIssue {
+                name: update.channel.name,
+                reason: format!("Could not load in feed. Error: {}", err),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::temp_dir;
+
+    /// Writes a sample export under `file_name` and parses it, detecting its format as an import would
+    fn parse_sample(file_name: &str, raw: &str) -> Result<(ImportFormat, Vec<ImportEntry>), Error> {
+        let path = temp_dir("import").join(file_name);
+        fs::write(&path, raw).unwrap();
+        parse(&path)
+    }
+
+    fn names_and_urls(entries: &[ImportEntry]) -> Vec<(&str, &str, bool)> {
+        entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.url.as_str(), entry.unsupported))
+            .collect()
+    }
+
+    #[test]
+    fn opml_outlines_are_read_from_any_folder() {
+        let raw = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="1.1">
+  <body>
+    <outline text="Tech">
+      <outline text="Linus" title="Linus Tech Tips" type="rss"
+        xmlUrl="https://www.youtube.com/feeds/videos.xml?channel_id=UClinus"/>
+    </outline>
+    <outline text="Course" htmlUrl="https://www.youtube.com/playlist?list=PLcourse"/>
+    <outline text="Folder without a feed"/>
+  </body>
+</opml>"#;
+
+        let (format, entries) = parse_sample("subscriptions.opml", raw).unwrap();
+        assert_eq!(format, ImportFormat::Opml);
+        assert_eq!(
+            names_and_urls(&entries),
+            vec![
+                (
+                    "Linus Tech Tips",
+                    "https://www.youtube.com/feeds/videos.xml?channel_id=UClinus",
+                    false
+                ),
+                (
+                    "Course",
+                    "https://www.youtube.com/playlist?list=PLcourse",
+                    false
+                ),
+            ]
+        );
+
+        assert!(matches!(
+            parse_sample("broken.opml", "<opml><body><outline"),
+            Err(Error::XmlParsing)
+        ));
+    }
+
+    #[test]
+    fn takeout_rows_are_found_under_any_header() {
+        let raw = "Kanal-ID,Kanal-URL,Kanaltitel\n\
+            UCfirst,http://www.youtube.com/channel/UCfirst,First\n\
+            UCquoted,http://www.youtube.com/channel/UCquoted,\"Quoted, \"\"Name\"\"\"\n\
+            not a row\n";
+
+        let (format, entries) = parse_sample("subscriptions.csv", raw).unwrap();
+        assert_eq!(format, ImportFormat::Takeout);
+        assert_eq!(
+            names_and_urls(&entries),
+            vec![
+                ("First", "http://www.youtube.com/channel/UCfirst", false),
+                (
+                    "Quoted, \"Name\"",
+                    "http://www.youtube.com/channel/UCquoted",
+                    false
+                ),
+            ]
+        );
+
+        // Takeout has no structure to reject, so anything else simply has no rows
+        let (_, entries) = parse_sample("garbage.csv", "\u{0}\u{1}, nothing here").unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn newpipe_subscriptions_from_other_services_are_unsupported() {
+        let raw = r#"{"app_version": "0.26.1", "subscriptions": [
+            {"service_id": 0, "url": "https://www.youtube.com/channel/UCfirst", "name": "First"},
+            {"service_id": 1, "url": "https://soundcloud.com/artist", "name": "Artist"}
+        ]}"#;
+
+        let (format, entries) = parse_sample("subscriptions.json", raw).unwrap();
+        assert_eq!(format, ImportFormat::NewPipe);
+        assert_eq!(
+            names_and_urls(&entries),
+            vec![
+                ("First", "https://www.youtube.com/channel/UCfirst", false),
+                ("Artist", "https://soundcloud.com/artist", true),
+            ]
+        );
+
+        assert!(matches!(
+            parse_sample("broken.json", r#"{"subscriptions": [{"name": "#),
+            Err(Error::JsonParsing)
+        ));
+    }
+
+    #[test]
+    fn freetube_keeps_the_latest_version_of_each_profile() {
+        let raw = [
+            r#"{"_id":"allChannels","name":"All Channels","subscriptions":[{"id":"UCold","name":"Old"}]}"#,
+            r#"{"_id":"work","name":"Work","subscriptions":[{"id":"UCwork","name":"Work"}]}"#,
+            r#"{"_id":"allChannels","name":"All Channels","subscriptions":[{"id":"UCnew","name":"New"}]}"#,
+            r#"{"$$deleted":true,"_id":"work"}"#,
+        ]
+        .join("\n");
+
+        let (format, entries) = parse_sample("profiles.db", &raw).unwrap();
+        assert_eq!(format, ImportFormat::FreeTube);
+        assert_eq!(
+            names_and_urls(&entries),
+            vec![("New", "https://www.youtube.com/channel/UCnew", false)]
+        );
+
+        assert!(matches!(
+            parse_sample("broken.db", "{\"_id\": \"allChannels\"\nnot json"),
+            Err(Error::JsonParsing)
+        ));
+        assert!(matches!(
+            parse_sample("empty.db", ""),
+            Err(Error::JsonParsing)
+        ));
+    }
+
+    #[test]
+    fn formats_without_an_extension_are_detected_from_their_contents() {
+        let detect = |raw: &str| ImportFormat::detect(Path::new("export"), raw);
+
+        assert_eq!(
+            detect("  <?xml version=\"1.0\"?><opml/>"),
+            ImportFormat::Opml
+        );
+        assert_eq!(detect("{\"subscriptions\": []}"), ImportFormat::NewPipe);
+        assert_eq!(
+            detect("{\"_id\":\"a\"}\n{\"_id\":\"b\"}"),
+            ImportFormat::FreeTube
+        );
+        assert_eq!(
+            detect("Channel Id,Channel Url,Channel Title"),
+            ImportFormat::Takeout
+        );
+    }
+}
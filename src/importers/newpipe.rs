@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use crate::view::Error;
+
+use super::ImportEntry;
+
+// NewPipe's service ID for YouTube
+const YOUTUBE_SERVICE: i64 = 0;
+
+#[derive(Deserialize)]
+struct NewPipeExport {
+    subscriptions: Vec<NewPipeSubscription>,
+}
+
+#[derive(Deserialize)]
+struct NewPipeSubscription {
+    service_id: i64,
+    url: String,
+    name: String,
+}
+
+/// Parses a NewPipe `subscriptions.json` export
+pub fn parse(raw: &str) -> Result<Vec<ImportEntry>, Error> {
+    let export: NewPipeExport = serde_json::from_str(raw).map_err(|_| Error::JsonParsing)?;
+
+    Ok(export
+        .subscriptions
+        .into_iter()
+        .map(|subscription| {
+            let mut entry = ImportEntry::new(subscription.name, subscription.url);
+            if subscription.service_id != YOUTUBE_SERVICE {
+                entry.unsupported = true;
+            }
+            entry
+        })
+        .collect())
+}
@@ -0,0 +1,67 @@
+use std::{fs, path::Path};
+
+use crate::{
+    view::Error,
+    yt::{ChannelInfo, Channels},
+};
+
+use super::ImportEntry;
+
+pub fn parse(raw: &str) -> Result<Vec<ImportEntry>, Error> {
+    let document = roxmltree::Document::parse(raw).map_err(|_| Error::XmlParsing)?;
+
+    // Outlines can be nested inside of folders/categories, so every descendant is checked
+    let entries = document
+        .descendants()
+        .filter(|node| node.has_tag_name("outline"))
+        .filter_map(|outline| {
+            let url = outline
+                .attribute("xmlUrl")
+                .or(outline.attribute("htmlUrl"))?;
+            let name = outline
+                .attribute("title")
+                .or(outline.attribute("text"))
+                .unwrap_or(url);
+
+            Some(ImportEntry::new(name, url))
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+pub fn to_string(channels: &Channels) -> String {
+    let mut opml = String::new();
+
+    opml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    opml.push_str("<opml version=\"1.1\">\n");
+    opml.push_str("  <head>\n    <title>yt-feeds subscriptions</title>\n  </head>\n");
+    opml.push_str("  <body>\n");
+
+    channels.iter().map(ChannelInfo::from).for_each(|channel| {
+        let name = escape(&channel.name);
+        opml.push_str(&format!(
+            "    <outline text=\"{}\" title=\"{}\" type=\"rss\" xmlUrl=\"{}\" htmlUrl=\"{}\"/>\n",
+            name,
+            name,
+            escape(&channel.feed_url()),
+            escape(&channel.url())
+        ));
+    });
+
+    opml.push_str("  </body>\n");
+    opml.push_str("</opml>\n");
+    opml
+}
+
+pub fn export(path: &Path, channels: &Channels) -> Result<(), Error> {
+    fs::write(path, to_string(channels)).map_err(|_| Error::FileBadAccess)
+}
+
+fn escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
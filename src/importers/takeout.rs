@@ -0,0 +1,33 @@
+use super::ImportEntry;
+
+/// Parses a Google Takeout `subscriptions.csv` with the columns: Channel Id, Channel Url, Channel Title
+pub fn parse(raw: &str) -> Vec<ImportEntry> {
+    raw.lines()
+        .map(split_csv_line)
+        // The header row is localized by Google, so rows are detected by their channel ID instead
+        .filter(|columns| columns.len() >= 3 && columns[0].starts_with("UC"))
+        .map(|columns| ImportEntry::new(columns[2].as_str(), columns[1].as_str()))
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => columns.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+
+    columns.push(current);
+    columns
+}
@@ -4,7 +4,7 @@ use crossterm::event;
 use crossterm::event::KeyCode;
 use crossterm::style::Stylize;
 use std::io::Read;
-use std::process::{exit, Child};
+use std::process::Child;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -122,13 +122,13 @@ where
                 return;
             }
 
-            if event::poll(Duration::from_millis(500)).unwrap() {
-                if let event::Event::Key(key_event) = event::read().unwrap() {
-                    if key_event.code == KeyCode::Char('c') {
-                        flags.set_running(false);
-                    } else if key_event.code == KeyCode::Char('d') {
-                        flags.set_detached(true);
-                    }
+            if event::poll(Duration::from_millis(500)).unwrap()
+                && let event::Event::Key(key_event) = event::read().unwrap()
+            {
+                if key_event.code == KeyCode::Char('c') {
+                    flags.set_running(false);
+                } else if key_event.code == KeyCode::Char('d') {
+                    flags.set_detached(true);
                 }
             }
         }
@@ -183,7 +183,7 @@ where
             return flags
                 .result
                 .try_lock()
-                .map_err(|e| Error::InternalError(e.to_string()))
+                .map_err(|e| Error::Internal(e.to_string()))
                 .map(|guard| *guard);
         }
     }
@@ -1,9 +1,8 @@
-use core::panic;
 use std::path::PathBuf;
 use std::process::{self, Command};
 use std::rc::Rc;
 use std::thread;
-use std::{io, sync::mpsc};
+use std::{env, io};
 
 use config::Config;
 use crossterm::execute;
@@ -12,24 +11,41 @@ use crossterm::{
     cursor,
     terminal::{self, ClearType},
 };
-use updates::{check_updates, fetch_updates, Blocking};
+use updates::{check_updates, fetch_updates, Blocking, Refresh, RefreshTarget};
 use view::{Message, ViewPage};
-use views::{feed_view, home_view, information_view, player_view, search_channel_view};
-use yt::{Channel, Channels};
+use views::{
+    feed_view, home_view, information_view, player_view, refresh_view, search_channel_view,
+};
+use yt::{Channel, ChannelTab, Channels};
 
+use crate::filters::Filters;
+use crate::groups::Groups;
+use crate::history::HistoryEntry;
 use crate::loading::run_while_loading;
-use crate::view::{LastSearch, PlayType};
-use crate::views::{search_video_view, watch_later_view};
-use crate::yt::{fetch_more_videos, VideoWatchLater};
+use crate::mpv::WatchProgress;
+use crate::progress::ProgressStore;
+use crate::sorting::{SortModes, SortedFeed};
+use crate::view::{LastSearch, PlayType, Visibility};
+use crate::views::{continue_view, history_view, import_view, search_video_view, watch_later_view};
+use crate::yt::{fetch_more_videos, ChannelInfo, VideoWatchLater};
 
 mod cache;
+mod cli;
 mod config;
+mod filters;
 mod finder;
+mod groups;
+mod history;
+mod importers;
 mod loading;
 mod log;
 mod mpv;
+mod output;
 mod page;
+mod progress;
 mod search;
+mod sorting;
+mod sources;
 mod updates;
 mod utilities;
 mod view;
@@ -42,54 +58,110 @@ pub struct AppState {
     root_dir: Option<PathBuf>,
     last_search: Option<LastSearch>,
     watch_later: Vec<VideoWatchLater>,
-    tx: mpsc::Sender<Channel>,
-    rx: mpsc::Receiver<Channel>,
+    groups: Groups,
+    progress: ProgressStore,
+    sort_modes: SortModes,
+    background_refresh: Option<Refresh>,
+    visibility: Visibility,
+    filters: Filters,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
+impl AppState {
+    fn new(config: &Config) -> Self {
         let channels_cached = cache::fetch_cached_channels();
-        let (tx, rx) = mpsc::channel::<Channel>();
-
-        if let Some(channels_cached) = channels_cached {
-            AppState {
-                channels: Channels::new(&channels_cached),
-                view: ViewPage::Home,
-                root_dir: cache::data_directory().ok(),
-                last_search: None,
-                watch_later: cache::fetch_watch_later_videos(),
-                tx,
-                rx,
-            }
+        let mut watch_later = cache::fetch_watch_later_videos();
+
+        let (mut channels, view) = if let Some(channels_cached) = channels_cached {
+            (Channels::new(&channels_cached), ViewPage::Home(None))
         } else {
-            AppState {
-                channels: Channels::default(),
-                view: ViewPage::SearchChannels,
-                root_dir: cache::data_directory().ok(),
-                watch_later: cache::fetch_watch_later_videos(),
-                last_search: None,
-                tx,
-                rx,
-            }
+            (Channels::default(), ViewPage::SearchChannels)
+        };
+
+        let progress = ProgressStore::load_or_migrate(&channels, &watch_later);
+        progress.hydrate(&mut channels, &mut watch_later);
+        let filters = Filters::new(&config.filters);
+        filters.apply(&mut channels, &mut watch_later);
+
+        AppState {
+            channels,
+            view,
+            root_dir: cache::data_directory().ok(),
+            last_search: None,
+            watch_later,
+            groups: Groups::load(),
+            progress,
+            sort_modes: SortModes::load(),
+            background_refresh: None,
+            visibility: Visibility {
+                hide_watched: config.hide_watched,
+                show_hidden: false,
+            },
+            filters,
         }
     }
 }
 
+/// Refreshes channels while showing progress, then reports the results.
+/// A single channel refreshing successfully goes straight back to the last view instead
+fn refresh_channels(
+    state: &mut AppState,
+    config: &Config,
+    channels: Vec<RefreshTarget>,
+    last_view: ViewPage,
+) {
+    let single = channels.len() == 1;
+    state.view = ViewPage::Refreshing(Rc::new(last_view.clone()));
+
+    let mut refresh = fetch_updates(
+        config.source(),
+        channels,
+        &config.channel_tabs(),
+        config.videos_per_channel,
+        config.refresh_workers,
+    );
+    let report = check_updates(
+        &mut refresh,
+        &mut state.channels,
+        &state.progress,
+        &state.filters,
+        Blocking::Wait,
+    );
+    try_cache_channels(&state.channels);
+
+    if !single || !report.failed.is_empty() {
+        state.view = ViewPage::RefreshReport(Rc::new(report), Rc::new(last_view));
+    }
+}
+
+/// Starts a background refresh of channels not refreshed within the configured age, if enabled
+fn refresh_stale_channels(state: &mut AppState, config: &Config) {
+    if config.auto_refresh_minutes == 0 || state.background_refresh.is_some() {
+        return;
+    }
+
+    let stale: Vec<RefreshTarget> = state
+        .channels
+        .iter()
+        .filter(|channel| channel.refresh.is_stale(config.auto_refresh_minutes))
+        .map(|channel| channel.into())
+        .collect();
+
+    if !stale.is_empty() {
+        state.background_refresh = Some(fetch_updates(
+            config.source(),
+            stale,
+            &config.channel_tabs(),
+            config.videos_per_channel,
+            config.refresh_workers,
+        ));
+    }
+}
+
 fn program_installed(command: &str) -> bool {
     Command::new(command).arg("--version").output().is_ok()
 }
 
 fn main() {
-    thread::spawn(|| {
-        if !program_installed("mpv") {
-            log::err_and_exit("mpv must be installed and locatable on your PATH.\nFor help, visit https://github.com/nate-craft/yt-feeds".red());
-        }
-
-        if !program_installed("yt-dlp") {
-            log::err_and_exit("yt-dlp must be installed and locatable on your PATH.\nFor help, visit https://github.com/nate-craft/yt-feeds".red());
-        }
-    });
-
     let config = match Config::load_or_default() {
         Ok(loaded) => loaded,
         Err(err) => {
@@ -100,47 +172,142 @@ fn main() {
         }
     };
 
-    let mut state = AppState::default();
+    // Run non-interactive commands without drawing any views
+    match cli::parse(&env::args().skip(1).collect::<Vec<String>>()) {
+        Ok(Some(command)) => {
+            if let Err(err) = cli::run(command, &config) {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        Ok(None) => {}
+        Err(usage) => {
+            eprintln!("{}", usage);
+            process::exit(1);
+        }
+    }
+
+    thread::spawn(|| {
+        if !program_installed("mpv") {
+            log::err_and_exit("mpv must be installed and locatable on your PATH.\nFor help, visit https://github.com/nate-craft/yt-feeds".red());
+        }
+
+        if !program_installed("yt-dlp") {
+            log::err_and_exit("yt-dlp must be installed and locatable on your PATH.\nFor help, visit https://github.com/nate-craft/yt-feeds".red());
+        }
+    });
+
+    let mut state = AppState::new(&config);
 
     // Auto update on startup
     if config.refresh_on_start {
-        updates::fetch_updates(
-            state.tx.clone(),
+        state.background_refresh = Some(fetch_updates(
+            config.source(),
             state
                 .channels
                 .iter()
                 .map(|channel| channel.into())
                 .collect(),
+            &config.channel_tabs(),
             config.videos_per_channel,
-        );
-        try_cache_channels(&state.channels);
+            config.refresh_workers,
+        ));
     }
 
     loop {
         // check for auto updates in background of each loop
-        if config.refresh_on_start {
-            check_updates(&state.rx, &mut state.channels, Blocking::NoWait);
+        if let Some(refresh) = state.background_refresh.as_mut() {
+            check_updates(
+                refresh,
+                &mut state.channels,
+                &state.progress,
+                &state.filters,
+                Blocking::NoWait,
+            );
+
+            if refresh.is_done() {
+                state.background_refresh = None;
+                try_cache_channels(&state.channels);
+            }
+        }
+        refresh_stale_channels(&mut state, &config);
+
+        // A channel, group or video may have been removed since the view was opened
+        while !state
+            .view
+            .exists(&state.channels, &state.groups, &state.watch_later)
+        {
+            state.view = state.view.fallback();
         }
 
         let message: Message = match state.view {
-            ViewPage::Home => home_view::show(&state.channels),
+            ViewPage::Home(last_index) => {
+                home_view::show(&state.channels, &state.groups, last_index)
+            }
             ViewPage::SearchChannels => search_channel_view::show(&state.channels, &config),
-            ViewPage::SearchVideos => search_video_view::show(&config, state.last_search.as_ref()),
-            ViewPage::WatchLater => watch_later_view::show(&state.watch_later),
+            ViewPage::SearchVideos => search_video_view::show(
+                &config,
+                state.last_search.as_ref(),
+                &state.filters,
+                state.visibility,
+            ),
+            ViewPage::WatchLater => watch_later_view::show(&state.watch_later, state.visibility),
+            ViewPage::ContinueWatching => continue_view::show(
+                &state.channels,
+                &state.watch_later,
+                &config,
+                state.visibility,
+            ),
+            ViewPage::History => {
+                history_view::show(&state.channels, &state.filters, state.visibility)
+            }
+            ViewPage::Import => import_view::show(&state.channels, &config),
             ViewPage::Refreshing(ref last_view) => last_view.as_ref().clone().into(),
-            ViewPage::MixedFeed(last_index) => feed_view::show_mixed(&state.channels, last_index),
-            ViewPage::ChannelFeed(channel_index, last_index) => {
-                feed_view::show_channel(channel_index, &state.channels, last_index)
+            ViewPage::RefreshReport(ref report, ref last_view) => {
+                refresh_view::show(report, &state.channels, last_view)
             }
+            ViewPage::MixedFeed(last_index) => feed_view::show_mixed(
+                &state.channels,
+                last_index,
+                state.visibility,
+                state.sort_modes.get(&SortedFeed::Mixed),
+            ),
+            ViewPage::NewFeed(last_index) => feed_view::show_new(
+                &state.channels,
+                last_index,
+                state.visibility,
+                state.sort_modes.get(&SortedFeed::New),
+            ),
+            ViewPage::GroupFeed(ref name, last_index) => feed_view::show_group(
+                name,
+                &state.groups,
+                &state.channels,
+                last_index,
+                state.visibility,
+                state.sort_modes.get(&SortedFeed::Group(name.clone())),
+            ),
+            ViewPage::ChannelFeed(ref channel_id, tab, last_index) => feed_view::show_channel(
+                channel_id,
+                tab,
+                &config.channel_tabs(),
+                &state.channels,
+                last_index,
+                state.visibility,
+                state
+                    .sort_modes
+                    .get(&SortedFeed::Channel(channel_id.0.clone())),
+            ),
             ViewPage::Play(ref play_type, ref last_view) => player_view::show(
                 &state.channels,
                 &state.watch_later,
+                &state.progress,
                 play_type,
                 last_view,
                 &config,
             ),
-            ViewPage::Information(video_index, ref last_view) => {
-                information_view::show(&state.channels, video_index, last_view.clone())
+            ViewPage::Information(ref video_id, ref last_view) => {
+                information_view::show(&state.channels, video_id, last_view.clone(), &config)
             }
         };
 
@@ -149,31 +316,125 @@ fn main() {
 }
 
 fn handle_message(message: Message, state: &mut AppState, config: &Config) {
+    // Leaving a channel's feed for another page marks everything in it as seen. Playing a video or opening its
+    // information returns to the feed, so neither counts as leaving
+    if let ViewPage::ChannelFeed(ref channel_id, _, _) = state.view
+        && (matches!(
+            message,
+            Message::Home
+                | Message::Quit
+                | Message::MixedFeed(..)
+                | Message::NewFeed(..)
+                | Message::GroupFeed(..)
+                | Message::WatchLater
+                | Message::ContinueWatching
+                | Message::History
+                | Message::SearchChannels
+                | Message::SearchVideos
+                | Message::SearchVideosClean
+                | Message::Import
+        ) || matches!(message, Message::ChannelFeed(ref other, _, _) if other.0 != channel_id.0))
+        && let Some(channel) = state.channels.channel_mut(channel_id)
+        && channel.mark_seen()
+    {
+        let channel = state.channels.channel(channel_id).unwrap();
+        try_cache_watch_later(state, channel);
+    }
+
     match message {
         Message::Home => {
             state.last_search = None;
-            state.view = ViewPage::Home
+            state.view = ViewPage::Home(None)
         }
         Message::MixedFeed(last_index) => state.view = ViewPage::MixedFeed(last_index),
-        Message::ChannelFeed(channel_index, last_index) => {
-            state.view = ViewPage::ChannelFeed(channel_index, last_index)
+        Message::NewFeed(last_index) => state.view = ViewPage::NewFeed(last_index),
+        Message::GroupFeed(name, last_index) => state.view = ViewPage::GroupFeed(name, last_index),
+        Message::ToggleGroup(name, last_index) => {
+            state.groups.toggle_collapsed(&name);
+            state.view = ViewPage::Home(Some(last_index));
+            try_cache_groups(&state.groups);
+        }
+        Message::SetGroup(channel_id, name, view_page) => {
+            state.groups.assign(&channel_id, name.as_deref());
+            state.view = view_page;
+            try_cache_groups(&state.groups);
+            state.sort_modes.prune(&state.channels, &state.groups);
+            try_cache_sort_modes(&state.sort_modes);
+        }
+        Message::MarkWatched(video_id, watched, view_page) => {
+            for channel in state.channels.iter_mut() {
+                if let Some(video) = channel.videos.iter_mut().find(|video| video.id == video_id) {
+                    video.watched = watched;
+                }
+            }
+            for later in state.watch_later.iter_mut() {
+                if later.video.id == video_id {
+                    later.video.watched = watched;
+                }
+            }
+
+            state.view = view_page;
+            state
+                .channels
+                .iter()
+                .filter(|channel| channel.videos.iter().any(|video| video.id == video_id))
+                .for_each(|channel| try_cache_watch_later(state, channel));
+            try_cache_watch_later_all(state);
+        }
+        Message::ToggleHideWatched(view_page) => {
+            state.visibility.hide_watched = !state.visibility.hide_watched;
+            state.view = view_page;
+        }
+        Message::ToggleShowHidden(view_page) => {
+            state.visibility.show_hidden = !state.visibility.show_hidden;
+            state.view = view_page;
+        }
+        Message::ToggleShowHiddenSearch(search) => {
+            state.visibility.show_hidden = !state.visibility.show_hidden;
+            state.last_search = Some(search);
+            state.view = ViewPage::SearchVideos;
+        }
+        Message::SetSortMode(mode, view_page) => {
+            if let Some(feed) = SortedFeed::of(&view_page) {
+                state.sort_modes.set(&feed, mode);
+                try_cache_sort_modes(&state.sort_modes);
+            }
+            state.view = view_page;
+        }
+        Message::MarkAllSeen => {
+            state.channels.iter_mut().for_each(|channel| {
+                channel.mark_seen();
+            });
+            state.view = ViewPage::NewFeed(None);
+            try_cache_channels(&state.channels);
+        }
+        Message::ChannelFeed(channel_id, tab, last_index) => {
+            state.view = ViewPage::ChannelFeed(channel_id, tab, last_index)
         }
         Message::WatchLater => state.view = ViewPage::WatchLater,
+        Message::ContinueWatching => state.view = ViewPage::ContinueWatching,
+        Message::History => state.view = ViewPage::History,
+        Message::Import => state.view = ViewPage::Import,
         Message::SearchChannels => state.view = ViewPage::SearchChannels,
         Message::SearchVideos => state.view = ViewPage::SearchVideos,
         Message::SearchVideosClean => {
             state.view = ViewPage::SearchVideos;
             state.last_search = None;
         }
-        Message::WatchLaterRemove(index) => {
+        Message::WatchLaterRemove(video_id) => {
             state.view = ViewPage::WatchLater;
-            state.watch_later.remove(index);
-            try_cache_watch_later_all(&state);
+            state.watch_later.retain(|later| !later.is(&video_id));
+            try_cache_watch_later_all(state);
         }
-        Message::WatchLaterAdd(video_info, last_view) => {
+        Message::WatchLaterAdd(mut video_info, last_view) => {
             state.view = (*last_view).clone();
+            let progress = state.progress.get(&video_info.video.id);
+            video_info
+                .video
+                .set_progress(progress, config.watched_percent);
+            state.filters.mark(&mut video_info.video);
             state.watch_later.push(video_info);
-            try_cache_watch_later_all(&state);
+            try_cache_watch_later_all(state);
         }
         Message::Play(play_type) => {
             if let PlayType::New(_, cached_search) = &play_type {
@@ -181,81 +442,167 @@ fn handle_message(message: Message, state: &mut AppState, config: &Config) {
             }
             state.view = ViewPage::Play(play_type, Rc::new(state.view.clone()));
         }
-        Message::Played(view_page, video_index, progress) => {
+        Message::Played(view_page, play_type, progress) => {
             state.view = view_page.as_ref().to_owned();
 
-            // do not cache single searched video playing
-            let Some(video_index) = video_index else {
+            let played = match play_type {
+                PlayType::Existing(video_id) => state
+                    .channels
+                    .channel_by_id(&video_id.channel_id)
+                    .and_then(|channel| {
+                        channel
+                            .video(&video_id)
+                            .map(|video| (video.id.clone(), video.title.clone(), channel.into()))
+                    }),
+                PlayType::WatchLater(video_id) => state
+                    .watch_later
+                    .iter()
+                    .find(|later| later.is(&video_id))
+                    .map(|later| {
+                        (
+                            later.video.id.clone(),
+                            later.video.title.clone(),
+                            later.channel.clone(),
+                        )
+                    }),
+                PlayType::New(info, _) => Some((info.id, info.title, info.channel)),
+            };
+
+            // Removed while playing, so there is nothing left to save progress for
+            let Some((video_id, title, channel)) = played else {
                 return;
             };
 
-            // save progress from played
-            let channel = state.channels.channel_mut(video_index.into()).unwrap();
-            channel.video_mut(video_index).unwrap().progress = progress;
-            let channel = state.channels.channel(video_index.into()).unwrap();
+            try_record_history(
+                &video_id,
+                &title,
+                &channel,
+                state.progress.get(&video_id),
+                progress,
+            );
+
+            // Keep what was saved before if mpv could not report where playback stopped
+            let progress = progress.or(state.progress.get(&video_id));
+            if let Some(progress) = progress {
+                state.progress.set(&video_id, progress);
+                if let Err(err) = state.progress.save() {
+                    log::err(format!("Could not save watch progress.\nError: {:?}", err));
+                }
+            }
+
+            // Update every copy of the video, whether in a channel or watch later
+            let mut played_channel = None;
+            for channel in state.channels.iter_mut() {
+                if let Some(video) = channel.videos.iter_mut().find(|video| video.id == video_id) {
+                    video.set_progress(progress, config.watched_percent);
+                    video.seen = true;
+                    played_channel = Some(channel.id.clone());
+                }
+            }
+            for later in state.watch_later.iter_mut() {
+                if later.video.id == video_id {
+                    later.video.set_progress(progress, config.watched_percent);
+                }
+            }
 
             // cache singular channel
-            try_cache_watch_later(&state, &channel);
+            if let Some(channel) = played_channel.and_then(|id| state.channels.channel_by_id(&id)) {
+                try_cache_watch_later(state, channel);
+            }
+            try_cache_watch_later_all(state);
         }
-        Message::Information(video_index, view_page) => {
-            state.view = ViewPage::Information(video_index, view_page);
+        Message::Information(video_id, view_page) => {
+            state.view = ViewPage::Information(video_id, view_page);
         }
-        Message::MoreInformation(video_index, view_page, new_description) => {
-            let channel = state.channels.channel_mut(video_index.into()).unwrap();
-            let video = channel.video_mut(video_index).unwrap();
-            video.description = new_description;
-            state.view = ViewPage::Information(video_index, view_page);
+        Message::MoreInformation(video_id, view_page, new_description) => {
+            if let Some(video) = state.channels.video_mut(&video_id) {
+                video.description = new_description;
+            }
+            state.view = ViewPage::Information(video_id, view_page);
         }
         Message::Subscribe(channel) => {
-            state.channels.push(channel);
-            state.view = ViewPage::Home;
+            state
+                .channels
+                .subscribe(channel, &state.progress, &state.filters);
+            state.view = ViewPage::Home(None);
+            try_cache_channels(&state.channels);
+        }
+        Message::SubscribeAll(channels) => {
+            channels.into_iter().for_each(|channel| {
+                state
+                    .channels
+                    .subscribe(channel, &state.progress, &state.filters)
+            });
+            state.view = ViewPage::Home(None);
             try_cache_channels(&state.channels);
         }
-        Message::Unsubscribe(channel_index) => {
-            state.channels.remove(*channel_index);
-            state.view = ViewPage::Home;
+        Message::Unsubscribe(channel_id) => {
+            state.channels.retain(|channel| channel.id != *channel_id);
+            state.groups.assign(&channel_id, None);
+            try_cache_groups(&state.groups);
+            state.sort_modes.prune(&state.channels, &state.groups);
+            try_cache_sort_modes(&state.sort_modes);
+            state.view = ViewPage::Home(None);
             try_cache_channels(&state.channels);
         }
         Message::Refresh(last_view) => {
-            state.view = ViewPage::Refreshing(Rc::new(last_view.clone()));
-            if let ViewPage::ChannelFeed(channel_index, _) = last_view {
-                let channel = state.channels.channel(channel_index).unwrap();
-                fetch_updates(
-                    state.tx.clone(),
-                    vec![channel.into()],
-                    config.videos_per_channel,
-                );
-                check_updates(&state.rx, &mut state.channels, Blocking::WaitForN(1));
+            let channels = if let ViewPage::ChannelFeed(ref channel_id, _, _) = last_view {
+                state
+                    .channels
+                    .channel(channel_id)
+                    .map(|channel| channel.into())
+                    .into_iter()
+                    .collect()
+            } else if let ViewPage::GroupFeed(ref name, _) = last_view {
+                state
+                    .channels
+                    .iter()
+                    .filter(|channel| {
+                        state
+                            .groups
+                            .group(name)
+                            .is_some_and(|group| group.channel_ids.contains(&channel.id))
+                    })
+                    .map(|channel| channel.into())
+                    .collect()
             } else {
-                fetch_updates(
-                    state.tx.clone(),
-                    state
-                        .channels
-                        .iter()
-                        .map(|channel| channel.into())
-                        .collect(),
-                    config.videos_per_channel,
-                );
-
-                let number_updates = state.channels.len();
-                check_updates(
-                    &state.rx,
-                    &mut state.channels,
-                    Blocking::WaitForN(number_updates),
-                );
-            }
-            try_cache_channels(&state.channels);
+                state
+                    .channels
+                    .iter()
+                    .map(|channel| channel.into())
+                    .collect()
+            };
+
+            refresh_channels(state, config, channels, last_view);
+        }
+        Message::RefreshChannels(channels, last_view) => {
+            let channels = channels
+                .iter()
+                .filter_map(|channel| state.channels.channel_by_id(&channel.id))
+                .map(|channel| channel.into())
+                .collect();
+
+            refresh_channels(state, config, channels, last_view);
         }
         Message::Quit => {
             clear_screen();
             process::exit(0);
         }
-        Message::MoreVideos(channel_index, view_page, video_count, last_viewed_index) => {
-            let channel = state.channels.channel_mut(channel_index).unwrap();
+        Message::MoreVideos(channel_id, view_page, video_count, last_viewed_index) => {
+            let progress = &state.progress;
+            let filters = &state.filters;
+            let Some(channel) = state.channels.channel_mut(&channel_id) else {
+                state.view = ViewPage::Home(None);
+                return;
+            };
             let name = channel.name.clone();
+            let tab = match view_page {
+                ViewPage::ChannelFeed(_, tab, _) => tab,
+                _ => ChannelTab::Videos,
+            };
 
             let success = run_while_loading(
-                || fetch_more_videos(config, video_count, channel),
+                || fetch_more_videos(config, tab, video_count, channel, progress, filters),
                 move || {
                     println!(
                         "{}{}\n",
@@ -275,16 +622,47 @@ fn handle_message(message: Message, state: &mut AppState, config: &Config) {
             }
 
             state.view = match view_page {
-                ViewPage::ChannelFeed(channel_index, _) => {
-                    ViewPage::ChannelFeed(channel_index, Some(last_viewed_index))
+                ViewPage::ChannelFeed(channel_id, tab, _) => {
+                    ViewPage::ChannelFeed(channel_id, tab, Some(last_viewed_index))
                 }
                 ViewPage::MixedFeed(_) => ViewPage::MixedFeed(Some(last_viewed_index)),
-                _ => panic!(),
+                view_page => view_page,
             }
         }
     }
 }
 
+fn try_record_history(
+    video_id: &str,
+    title: &str,
+    channel: &ChannelInfo,
+    before: Option<WatchProgress>,
+    after: Option<WatchProgress>,
+) {
+    let Some(after) = after else {
+        return;
+    };
+
+    let start = before.map(|progress| progress.current).unwrap_or(0);
+    let entry = HistoryEntry::new(video_id, title, channel, start, after.current);
+
+    if let Err(err) = history::record(&entry) {
+        log::err(format!("Could not save watch history.\nError: {:?}", err));
+    }
+}
+
+fn try_cache_groups(groups: &Groups) {
+    if let Err(err) = groups.save() {
+        log::err(format!("Could not save channel groups.\nError: {:?}", err));
+    }
+}
+
+fn try_cache_sort_modes(sort_modes: &SortModes) {
+    if let Err(err) = sort_modes.save() {
+        log::err(format!("Could not save sort modes.\nError: {:?}", err));
+    }
+}
+
 fn try_cache_channels(channels: &Channels) {
     if let Err(err) = cache::cache_channels(channels) {
         log::err(format!(
@@ -295,24 +673,24 @@ fn try_cache_channels(channels: &Channels) {
 }
 
 fn try_cache_watch_later(state: &AppState, channel: &Channel) {
-    if let Some(root) = &state.root_dir {
-        if let Err(err) = cache::cache_videos(root, &channel.id, &channel.videos) {
-            log::err(format!(
-                "Could not retrieve local data directory. Caching cannot be enabled!\nError: {:?}",
-                err
-            ));
-        }
+    if let Some(root) = &state.root_dir
+        && let Err(err) = cache::cache_videos(root, &channel.id, &channel.videos)
+    {
+        log::err(format!(
+            "Could not retrieve local data directory. Caching cannot be enabled!\nError: {:?}",
+            err
+        ));
     }
 }
 
 fn try_cache_watch_later_all(state: &AppState) {
-    if let Some(root) = &state.root_dir {
-        if let Err(err) = cache::cache_watch_later(root, &state.watch_later) {
-            log::err(format!(
-                "Could not cache watch_history. Progress will not be saved!\nError: {:?}",
-                err
-            ));
-        }
+    if let Some(root) = &state.root_dir
+        && let Err(err) = cache::cache_watch_later(root, &state.watch_later)
+    {
+        log::err(format!(
+            "Could not cache watch_history. Progress will not be saved!\nError: {:?}",
+            err
+        ));
     }
 }
 
@@ -1,16 +1,20 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::utilities::seconds_formatted;
 
-pub const MPV_SOCKET: &'static str = "/tmp/yt-feeds-socket";
+pub const MPV_SOCKET: &str = "/tmp/yt-feeds-socket";
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct WatchProgress {
     pub current: u32,
     pub duration: u32,
+    /// When this progress was read from mpv. Missing for progress saved before it was tracked
+    #[serde(default)]
+    pub played: Option<DateTime<Local>>,
 }
 
 enum MpvCommand {
@@ -23,7 +27,11 @@ enum MpvResult {
 
 impl WatchProgress {
     pub fn new(current: u32, duration: u32) -> WatchProgress {
-        WatchProgress { current, duration }
+        WatchProgress {
+            current,
+            duration,
+            played: Some(Local::now()),
+        }
     }
 
     pub fn playing() -> Option<WatchProgress> {
@@ -34,7 +42,7 @@ impl WatchProgress {
             return Some(progress);
         }
 
-        return None;
+        None
     }
 
     pub fn formatted(&self) -> String {
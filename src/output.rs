@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::yt::{Video, VideoWatchLater};
+
+/// Bumped whenever a field is removed or changes meaning. New fields may be added without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Machine readable output, kept separate from the cached structs so scripts do not break when they change
+#[derive(Serialize)]
+pub struct VideosOutput {
+    pub version: u32,
+    pub videos: Vec<VideoOutput>,
+}
+
+#[derive(Serialize)]
+pub struct VideoOutput {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub upload: String,
+    pub upload_timestamp: i64,
+    pub duration_seconds: Option<u32>,
+    pub view_count: Option<u64>,
+    pub progress: Option<ProgressOutput>,
+    pub watched: bool,
+}
+
+#[derive(Serialize)]
+pub struct ProgressOutput {
+    pub current_seconds: u32,
+    pub duration_seconds: u32,
+}
+
+impl VideosOutput {
+    pub fn new(videos: Vec<VideoOutput>) -> VideosOutput {
+        VideosOutput {
+            version: SCHEMA_VERSION,
+            videos,
+        }
+    }
+}
+
+impl VideoOutput {
+    pub fn new(video: &Video, channel_id: &str, channel_name: &str) -> VideoOutput {
+        VideoOutput {
+            id: video.id.clone(),
+            title: video.title.clone(),
+            url: video.url(),
+            channel_id: channel_id.to_owned(),
+            channel_name: channel_name.to_owned(),
+            upload: video.upload.to_rfc3339(),
+            upload_timestamp: video.upload.timestamp(),
+            duration_seconds: video.duration,
+            view_count: video.view_count,
+            progress: video.progress.map(|progress| ProgressOutput {
+                current_seconds: progress.current,
+                duration_seconds: progress.duration,
+            }),
+            watched: video.watched,
+        }
+    }
+}
+
+impl From<&VideoWatchLater> for VideoOutput {
+    fn from(value: &VideoWatchLater) -> Self {
+        VideoOutput::new(&value.video, &value.channel.id, &value.channel.name)
+    }
+}
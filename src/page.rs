@@ -28,10 +28,6 @@ impl Page {
         &videos[self.current_index..(self.current_index + self.count_per_page)]
     }
 
-    pub fn last_index(&self) -> usize {
-        self.count_total
-    }
-
     pub fn pages_count(&self) -> usize {
         (self.count_total as f32 / max(self.count_per_page, 1) as f32).ceil() as usize
     }
@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::data_directory,
+    log,
+    mpv::WatchProgress,
+    view::Error,
+    yt::{Channels, Video, VideoWatchLater},
+};
+
+/// Watch progress for every played video keyed by video ID, saved in `progress.json`.
+/// `Video.progress` is only a copy of this for display
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProgressStore {
+    progress: HashMap<String, WatchProgress>,
+    /// Set when an unreadable `progress.json` could not be moved aside, so it is never saved over
+    #[serde(skip)]
+    locked: bool,
+}
+
+impl ProgressStore {
+    /// Loads the store, building it from progress saved on cached videos the first time.
+    /// A store that can not be read is moved to `progress.json.bak` rather than saved over, as it is the only copy
+    pub fn load_or_migrate(channels: &Channels, watch_later: &[VideoWatchLater]) -> ProgressStore {
+        let Ok(path) = data_directory().map(|root| root.join("progress.json")) else {
+            return ProgressStore::default();
+        };
+
+        if Path::exists(&path) {
+            let store = File::open(&path)
+                .ok()
+                .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok());
+
+            if let Some(store) = store {
+                return store;
+            }
+
+            let backup = path.with_extension("json.bak");
+            match fs::rename(&path, &backup) {
+                Ok(()) => log::err(format!(
+                    "Could not load json for {:?}, so it was moved to {:?}\n",
+                    path, backup
+                )),
+                Err(err) => {
+                    // Leave the file alone and keep progress in memory only, rather than lose it
+                    log::err(format!(
+                        "Could not load json for {:?} or move it aside. Progress will not be saved!\nError: {}",
+                        path, err
+                    ));
+                    return ProgressStore {
+                        locked: true,
+                        ..ProgressStore::default()
+                    };
+                }
+            }
+        }
+
+        let mut store = ProgressStore::default();
+        channels
+            .iter()
+            .flat_map(|channel| channel.videos.iter())
+            .chain(watch_later.iter().map(|later| &later.video))
+            .for_each(|video| store.migrate(video));
+
+        if let Err(err) = store.save() {
+            log::err(format!("Could not save watch progress.\nError: {:?}", err));
+        }
+        store
+    }
+
+    /// Keeps whichever copy of a video's progress was played last, or got furthest if neither says
+    fn migrate(&mut self, video: &Video) {
+        let Some(progress) = video.progress else {
+            return;
+        };
+
+        let newer = self.progress.get(&video.id).is_none_or(|existing| {
+            (progress.played, progress.current) > (existing.played, existing.current)
+        });
+
+        if newer {
+            self.progress.insert(video.id.clone(), progress);
+        }
+    }
+
+    /// Writes to a temporary file first and moves it over `progress.json`, so a failed save never leaves it half written
+    pub fn save(&self) -> Result<(), Error> {
+        if self.locked {
+            return Err(Error::FileBadAccess);
+        }
+
+        let path = data_directory()?.join("progress.json");
+        let temporary = path.with_extension("json.tmp");
+        let file = File::create(&temporary).map_err(|_| Error::FileBadAccess)?;
+
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, self).map_err(|_| Error::JsonParsing)?;
+        writer
+            .into_inner()
+            .map_err(|_| Error::FileBadAccess)?
+            .sync_all()
+            .map_err(|_| Error::FileBadAccess)?;
+
+        fs::rename(&temporary, &path).map_err(|_| Error::FileBadAccess)
+    }
+
+    pub fn get(&self, video_id: &str) -> Option<WatchProgress> {
+        self.progress.get(video_id).copied()
+    }
+
+    pub fn set(&mut self, video_id: &str, progress: WatchProgress) {
+        self.progress.insert(video_id.to_owned(), progress);
+    }
+
+    /// Copies stored progress onto every cached video so views can show it
+    pub fn hydrate(&self, channels: &mut Channels, watch_later: &mut [VideoWatchLater]) {
+        channels
+            .iter_mut()
+            .flat_map(|channel| channel.videos.iter_mut())
+            .chain(watch_later.iter_mut().map(|later| &mut later.video))
+            .for_each(|video| self.hydrate_video(video));
+    }
+
+    /// Copies stored progress onto a video as it is fetched, as it may have been played before it was subscribed to
+    pub fn hydrate_video(&self, video: &mut Video) {
+        video.progress = self.get(&video.id);
+    }
+}
@@ -1,12 +1,10 @@
-use std::process::Command;
-
 use chrono::{DateTime, Local};
 use itertools::Itertools;
 use serde_json::Value;
 
 use crate::{
     view::Error,
-    yt::{ChannelInfo, VideoInfo},
+    yt::{ChannelInfo, SubscriptionKind, VideoInfo},
 };
 
 #[derive(Default)]
@@ -24,6 +22,8 @@ pub struct VideoInfoAccumulator {
     upload: Option<DateTime<Local>>,
     available: bool,
     is_short: bool,
+    duration: Option<u32>,
+    view_count: Option<u64>,
 }
 
 impl ChannelInfoAccumulator {
@@ -44,6 +44,7 @@ impl TryFrom<ChannelInfoAccumulator> for ChannelInfo {
         Ok(ChannelInfo {
             id: value.id.ok_or(Error::ChannelParsing)?,
             name: value.name.ok_or(Error::ChannelParsing)?,
+            kind: SubscriptionKind::Channel,
         })
     }
 }
@@ -69,6 +70,10 @@ impl VideoInfoAccumulator {
         } else if key.eq("timestamp") {
             self.upload = DateTime::from_timestamp(value.as_i64().unwrap_or(0), 0)
                 .map(|time| time.with_timezone(&Local));
+        } else if key.eq("duration") {
+            self.duration = value.as_f64().map(|duration| duration as u32);
+        } else if key.eq("view_count") {
+            self.view_count = value.as_u64();
         }
         self
     }
@@ -87,23 +92,17 @@ impl TryFrom<VideoInfoAccumulator> for VideoInfo {
             channel: ChannelInfo {
                 id: value.channel_id.ok_or(Error::VideoParsing)?,
                 name: value.channel_name.ok_or(Error::VideoParsing)?,
+                kind: SubscriptionKind::Channel,
             },
+            duration: value.duration,
+            view_count: value.view_count,
         })
     }
 }
 
-pub fn fetch_channel(channel: &str, queries: usize) -> Vec<ChannelInfo> {
-    let channel_json_bytes = Command::new("yt-dlp")
-        .arg("--flat-playlist")
-        .arg("--dump-json")
-        .arg(format!("ytsearch{}:{}", queries, channel))
-        .output()
-        .expect("Could not find command yt-dlp")
-        .stdout;
-
-    String::from_utf8_lossy(&channel_json_bytes)
-        .to_string()
-        .trim()
+/// Parses the line separated JSON of a `yt-dlp --flat-playlist --dump-json` search into channels
+pub fn parse_channels(raw: &str) -> Vec<ChannelInfo> {
+    raw.trim()
         .lines()
         .filter_map(|line| -> Option<Value> { serde_json::from_str(line).ok() })
         .filter_map(|json: Value| -> Option<ChannelInfo> {
@@ -121,20 +120,9 @@ pub fn fetch_channel(channel: &str, queries: usize) -> Vec<ChannelInfo> {
         .collect()
 }
 
-pub fn fetch_videos(query: &str, queries: usize) -> Vec<VideoInfo> {
-    let json_bytes = Command::new("yt-dlp")
-        .arg("--flat-playlist")
-        .arg("--dump-json")
-        .arg("--extractor-args")
-        .arg("youtubetab:approximate_date")
-        .arg(format!("ytsearch{}:{}", queries, query))
-        .output()
-        .expect("Could not find command yt-dlp")
-        .stdout;
-
-    String::from_utf8_lossy(&json_bytes)
-        .to_string()
-        .trim()
+/// Parses the line separated JSON of a `yt-dlp --flat-playlist --dump-json` search into videos
+pub fn parse_videos(raw: &str) -> Vec<VideoInfo> {
+    raw.trim()
         .lines()
         .filter_map(|line| -> Option<Value> { serde_json::from_str(line).ok() })
         .filter_map(|json: Value| -> Option<VideoInfo> {
@@ -151,3 +139,31 @@ pub fn fetch_videos(query: &str, queries: usize) -> Vec<VideoInfo> {
         .unique()
         .collect()
 }
+
+/// Parses the single JSON object of a channel page into its channel information
+pub fn parse_channel(raw: &str) -> Result<ChannelInfo, Error> {
+    let json: Value = serde_json::from_str(raw).map_err(|_| Error::ChannelParsing)?;
+
+    json.as_object()
+        .ok_or(Error::ChannelParsing)?
+        .iter()
+        .fold(
+            ChannelInfoAccumulator::default(),
+            ChannelInfoAccumulator::accumulate,
+        )
+        .try_into()
+}
+
+/// Parses the single JSON object of a playlist page into a playlist subscription
+pub fn parse_playlist(raw: &str) -> Result<ChannelInfo, Error> {
+    let json: Value = serde_json::from_str(raw).map_err(|_| Error::ChannelParsing)?;
+
+    Ok(ChannelInfo {
+        id: json["id"].as_str().ok_or(Error::ChannelParsing)?.to_owned(),
+        name: json["title"]
+            .as_str()
+            .ok_or(Error::ChannelParsing)?
+            .to_owned(),
+        kind: SubscriptionKind::Playlist,
+    })
+}
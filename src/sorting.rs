@@ -0,0 +1,272 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::data_directory,
+    groups::Groups,
+    log,
+    view::{Error, ViewPage},
+    yt::{Channels, Video},
+};
+
+/// How a feed's videos are ordered
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    #[default]
+    Newest,
+    Oldest,
+    Shortest,
+    Longest,
+    UnwatchedFirst,
+    LeastRemaining,
+    Channel,
+}
+
+/// The feeds that each remember their own sort mode, with every channel and group remembered separately
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SortedFeed {
+    /// A channel or playlist by ID
+    Channel(String),
+    Mixed,
+    New,
+    /// A group by name
+    Group(String),
+}
+
+/// The sort mode chosen for each feed, saved in `sort_modes.json`. Keyed by the kind of feed, such as `channel`,
+/// which is the default for feeds of that kind, or by a single channel or group, such as `channel:<id>`
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SortModes(HashMap<String, SortMode>);
+
+impl SortMode {
+    /// Every mode, in the order they are offered
+    pub const ALL: [SortMode; 7] = [
+        SortMode::Newest,
+        SortMode::Oldest,
+        SortMode::Shortest,
+        SortMode::Longest,
+        SortMode::UnwatchedFirst,
+        SortMode::LeastRemaining,
+        SortMode::Channel,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Newest => "Newest",
+            SortMode::Oldest => "Oldest",
+            SortMode::Shortest => "Shortest",
+            SortMode::Longest => "Longest",
+            SortMode::UnwatchedFirst => "Unwatched",
+            SortMode::LeastRemaining => "Least Left",
+            SortMode::Channel => "Channel",
+        }
+    }
+
+    /// Orders two videos along with their channel names. Ties, and videos missing a duration, fall back to newest first
+    pub fn compare(
+        &self,
+        (channel_a, a): (&str, &Video),
+        (channel_b, b): (&str, &Video),
+    ) -> Ordering {
+        let newest = b.upload.cmp(&a.upload);
+
+        let ordering = match self {
+            SortMode::Newest => Ordering::Equal,
+            SortMode::Oldest => a.upload.cmp(&b.upload),
+            SortMode::Shortest => {
+                (a.duration.is_none(), a.duration).cmp(&(b.duration.is_none(), b.duration))
+            }
+            SortMode::Longest => (a.duration.is_none(), Reverse(a.duration))
+                .cmp(&(b.duration.is_none(), Reverse(b.duration))),
+            SortMode::UnwatchedFirst => a.watched.cmp(&b.watched),
+            SortMode::LeastRemaining => {
+                let key = |video: &Video| {
+                    let remaining = video.remaining();
+                    (video.watched, remaining.is_none(), remaining)
+                };
+                key(a).cmp(&key(b))
+            }
+            SortMode::Channel => channel_a.to_lowercase().cmp(&channel_b.to_lowercase()),
+        };
+
+        ordering.then(newest)
+    }
+}
+
+impl SortedFeed {
+    pub fn of(view: &ViewPage) -> Option<SortedFeed> {
+        match view {
+            ViewPage::ChannelFeed(channel_id, _, _) => {
+                Some(SortedFeed::Channel(channel_id.0.clone()))
+            }
+            ViewPage::MixedFeed(_) => Some(SortedFeed::Mixed),
+            ViewPage::NewFeed(_) => Some(SortedFeed::New),
+            ViewPage::GroupFeed(name, _) => Some(SortedFeed::Group(name.clone())),
+            _ => None,
+        }
+    }
+
+    /// The modes offered for this feed, ordering by channel name is left out of a single channel's feed
+    pub fn modes(&self) -> Vec<SortMode> {
+        SortMode::ALL
+            .into_iter()
+            .filter(|mode| !matches!((self, mode), (SortedFeed::Channel(_), SortMode::Channel)))
+            .collect()
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            SortedFeed::Channel(_) => "channel",
+            SortedFeed::Mixed => "mixed",
+            SortedFeed::New => "new",
+            SortedFeed::Group(_) => "group",
+        }
+    }
+
+    fn key(&self) -> String {
+        match self {
+            SortedFeed::Channel(id) => format!("{}:{}", self.kind(), id),
+            SortedFeed::Group(name) => format!("{}:{}", self.kind(), name),
+            SortedFeed::Mixed | SortedFeed::New => self.kind().to_owned(),
+        }
+    }
+}
+
+impl SortModes {
+    pub fn load() -> SortModes {
+        let Ok(path) = data_directory().map(|root| root.join("sort_modes.json")) else {
+            return SortModes::default();
+        };
+
+        if !Path::exists(&path) {
+            return SortModes::default();
+        }
+
+        File::open(&path)
+            .ok()
+            .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+            .unwrap_or_else(|| {
+                log::err(format!("Could not load json for {:?}\n", path));
+                SortModes::default()
+            })
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let path = data_directory()?.join("sort_modes.json");
+        let file = File::create(path).map_err(|_| Error::FileBadAccess)?;
+        serde_json::to_writer(BufWriter::new(file), self).map_err(|_| Error::JsonParsing)
+    }
+
+    /// A feed's own mode, falling back to the one for its kind of feed
+    pub fn get(&self, feed: &SortedFeed) -> SortMode {
+        self.0
+            .get(&feed.key())
+            .or_else(|| self.0.get(feed.kind()))
+            .copied()
+            .filter(|mode| feed.modes().contains(mode))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, feed: &SortedFeed, mode: SortMode) {
+        self.0.insert(feed.key(), mode);
+    }
+
+    /// Forgets the modes of channels no longer subscribed to and groups that no longer exist
+    pub fn prune(&mut self, channels: &Channels, groups: &Groups) {
+        self.0.retain(|key, _| match key.split_once(':') {
+            Some(("channel", id)) => channels.has_channel(id),
+            Some(("group", name)) => groups.group(name).is_some(),
+            _ => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yt::Channel;
+
+    #[test]
+    fn channels_and_groups_remember_their_own_mode() {
+        let mut modes = SortModes::default();
+        let course = SortedFeed::Channel("UCcourse".to_owned());
+        let other = SortedFeed::Channel("UCother".to_owned());
+
+        modes.set(&course, SortMode::Oldest);
+        modes.set(&SortedFeed::Group("Music".to_owned()), SortMode::Shortest);
+
+        assert_eq!(modes.get(&course), SortMode::Oldest);
+        assert_eq!(modes.get(&other), SortMode::Newest);
+        assert_eq!(
+            modes.get(&SortedFeed::Group("Music".to_owned())),
+            SortMode::Shortest
+        );
+        assert_eq!(
+            modes.get(&SortedFeed::Group("News".to_owned())),
+            SortMode::Newest
+        );
+        assert_eq!(modes.get(&SortedFeed::Mixed), SortMode::Newest);
+    }
+
+    #[test]
+    fn modes_for_a_kind_of_feed_are_the_default() {
+        let mut modes: SortModes =
+            serde_json::from_str(r#"{"channel": "longest", "mixed": "unwatched-first"}"#).unwrap();
+        let course = SortedFeed::Channel("UCcourse".to_owned());
+
+        assert_eq!(modes.get(&course), SortMode::Longest);
+        assert_eq!(modes.get(&SortedFeed::Mixed), SortMode::UnwatchedFirst);
+
+        modes.set(&course, SortMode::Oldest);
+        assert_eq!(modes.get(&course), SortMode::Oldest);
+        assert_eq!(
+            modes.get(&SortedFeed::Channel("UCother".to_owned())),
+            SortMode::Longest
+        );
+    }
+
+    #[test]
+    fn channel_feeds_are_not_sorted_by_channel() {
+        let course = SortedFeed::Channel("UCcourse".to_owned());
+        let mut modes: SortModes = serde_json::from_str(r#"{"channel": "channel"}"#).unwrap();
+
+        assert!(!course.modes().contains(&SortMode::Channel));
+        assert!(SortedFeed::Mixed.modes().contains(&SortMode::Channel));
+        assert_eq!(modes.get(&course), SortMode::Newest);
+
+        modes.set(&SortedFeed::Group("Music".to_owned()), SortMode::Channel);
+        assert_eq!(
+            modes.get(&SortedFeed::Group("Music".to_owned())),
+            SortMode::Channel
+        );
+    }
+
+    #[test]
+    fn unsubscribed_channels_and_removed_groups_are_forgotten() {
+        let channels = Channels(vec![Channel::new("Kept", "UCkept", Vec::new())]);
+        let mut groups = Groups::default();
+        groups.assign("UCkept", Some("Music"));
+
+        let mut modes = SortModes::default();
+        modes.set(&SortedFeed::Mixed, SortMode::Oldest);
+        modes.set(&SortedFeed::Channel("UCkept".to_owned()), SortMode::Oldest);
+        modes.set(&SortedFeed::Channel("UCgone".to_owned()), SortMode::Oldest);
+        modes.set(&SortedFeed::Group("music".to_owned()), SortMode::Longest);
+        modes.set(&SortedFeed::Group("News".to_owned()), SortMode::Longest);
+
+        modes.prune(&channels, &groups);
+
+        let mut keys: Vec<&str> = modes.0.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["channel:UCkept", "group:music", "mixed"]);
+    }
+}
@@ -0,0 +1,144 @@
+use std::{fs, path::PathBuf};
+
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::{
+    search::{parse_channel, parse_channels, parse_playlist, parse_videos},
+    utilities::expand_path,
+    view::Error,
+    yt::{
+        channel_id_from_url, parse_channel_tab, playlist_id_from_url, ChannelInfo, ChannelTab,
+        Video, VideoInfo,
+    },
+};
+
+use super::VideoSource;
+
+/// Serves canned yt-dlp output from a directory instead of the network:
+///
+/// - `channels/<channel id>.jsonl`: `--flat-playlist --dump-json` output of a channel's videos
+/// - `channels/<channel id>.<streams|shorts>.jsonl`: the same for a channel's streams or shorts tab
+/// - `search.jsonl`: `--flat-playlist --dump-json` output of a search, used for channel and video searches
+/// - `videos/<video id>.json`: `--dump-json` output of a single video
+/// - `playlists/<playlist id>.jsonl`: `--flat-playlist --dump-json` output of a playlist's videos
+/// - `playlists/<playlist id>.json`: `--flat-playlist --dump-single-json` output of a playlist
+pub struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new(root: &str) -> Fixture {
+        Fixture {
+            root: expand_path(root),
+        }
+    }
+
+    fn read(&self, path: impl Into<PathBuf>) -> Result<String, Error> {
+        fs::read_to_string(self.root.join(path.into())).map_err(|_| Error::FileBadAccess)
+    }
+
+    fn read_feed(
+        &self,
+        path: impl Into<PathBuf>,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        let raw = self.read(path)?;
+
+        // Mirrors the range given to yt-dlp's --playlist-items
+        let lines = raw
+            .lines()
+            .skip(start.unwrap_or(1).saturating_sub(1))
+            .take(count)
+            .join("\n");
+
+        parse_channel_tab(&lines, tab)
+    }
+}
+
+impl VideoSource for Fixture {
+    fn fetch_channel_feed(
+        &self,
+        channel_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fetch_channel_tab(channel_id, ChannelTab::Videos, count, start)
+    }
+
+    fn fetch_channel_tab(
+        &self,
+        channel_id: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        let path = match tab {
+            ChannelTab::Videos => format!("channels/{}.jsonl", channel_id),
+            tab => format!("channels/{}.{}.jsonl", channel_id, tab.path()),
+        };
+
+        self.read_feed(path, tab, count, start)
+    }
+
+    fn fetch_video_description(&self, video: &Video) -> Result<String, Error> {
+        let Ok(raw) = self.read(format!("videos/{}.json", video.id)) else {
+            return Ok(video.description.clone());
+        };
+
+        serde_json::from_str(&raw)
+            .map_err(|_| Error::JsonParsing)
+            .and_then(|json: Value| {
+                json["description"]
+                    .as_str()
+                    .ok_or(Error::JsonParsing)
+                    .map(|str| str.to_owned())
+            })
+    }
+
+    fn fetch_channels(&self, query: &str, count: usize) -> Result<Vec<ChannelInfo>, Error> {
+        let raw = self.read("search.jsonl").unwrap_or_default();
+        Ok(parse_channels(&raw)
+            .into_iter()
+            .filter(|channel| channel.name.to_lowercase().contains(&query.to_lowercase()))
+            .take(count)
+            .collect())
+    }
+
+    fn fetch_videos(&self, query: &str, count: usize) -> Result<Vec<VideoInfo>, Error> {
+        let raw = self.read("search.jsonl").unwrap_or_default();
+        Ok(parse_videos(&raw)
+            .into_iter()
+            .filter(|video| video.title.to_lowercase().contains(&query.to_lowercase()))
+            .take(count)
+            .collect())
+    }
+
+    fn fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        let id = channel_id_from_url(url).ok_or(Error::ChannelParsing)?;
+        let raw = self.read(format!("channels/{}.jsonl", id))?;
+
+        parse_channel(raw.lines().next().unwrap_or_default())
+    }
+
+    fn fetch_playlist_feed(
+        &self,
+        playlist_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.read_feed(
+            format!("playlists/{}.jsonl", playlist_id),
+            ChannelTab::Videos,
+            count,
+            start,
+        )
+    }
+
+    fn fetch_playlist_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        let id = playlist_id_from_url(url).ok_or(Error::ChannelParsing)?;
+        parse_playlist(&self.read(format!("playlists/{}.json", id))?)
+    }
+}
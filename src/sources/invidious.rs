@@ -0,0 +1,468 @@
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    log,
+    view::Error,
+    yt::{channel_id_from_url, ChannelInfo, ChannelTab, SubscriptionKind, Video, VideoInfo},
+};
+
+use super::{http_get, ytdlp::YtDlp, VideoSource};
+
+/// Fetches from an Invidious instance's REST API, falling back to yt-dlp whenever the instance errors
+pub struct Invidious {
+    base_url: String,
+    fallback: YtDlp,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InvidiousVideo {
+    title: String,
+    video_id: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    author_id: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    published: i64,
+    #[serde(default)]
+    is_upcoming: bool,
+    #[serde(default)]
+    premiere_timestamp: Option<i64>,
+    #[serde(default)]
+    length_seconds: Option<u32>,
+    #[serde(default)]
+    view_count: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InvidiousChannel {
+    author: String,
+    author_id: String,
+}
+
+#[derive(Deserialize)]
+struct InvidiousChannelVideos {
+    videos: Vec<InvidiousVideo>,
+    continuation: Option<String>,
+}
+
+impl Invidious {
+    pub fn new(base_url: &str, fallback: YtDlp) -> Invidious {
+        Invidious {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            fallback,
+        }
+    }
+
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value, Error> {
+        if self.base_url.is_empty() {
+            return Err(Error::RequestFailed(
+                "No Invidious instance is configured".to_owned(),
+            ));
+        }
+
+        let raw = http_get(&format!("{}/api/v1/{}", self.base_url, path), query)?;
+        serde_json::from_str(&raw).map_err(|_| Error::JsonParsing)
+    }
+
+    fn search<T: for<'de> Deserialize<'de>>(
+        &self,
+        query: &str,
+        kind: &str,
+        count: usize,
+    ) -> Result<Vec<T>, Error> {
+        let mut results: Vec<T> = Vec::new();
+        let mut page = 1;
+
+        while results.len() < count {
+            let json = self.get(
+                "search",
+                &[("q", query), ("type", kind), ("page", &page.to_string())],
+            )?;
+
+            let Value::Array(found) = json else {
+                return Err(Error::JsonParsing);
+            };
+
+            if found.is_empty() {
+                break;
+            }
+
+            found
+                .into_iter()
+                .filter_map(|result| serde_json::from_value(result).ok())
+                .for_each(|result| results.push(result));
+            page += 1;
+        }
+
+        results.truncate(count);
+        Ok(results)
+    }
+
+    fn try_fetch_channel_feed(
+        &self,
+        channel_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        // Mirrors the range given to yt-dlp's --playlist-items
+        let skip = start.unwrap_or(1).saturating_sub(1);
+        let mut found: Vec<InvidiousVideo> = Vec::new();
+        let mut continuation: Option<String> = None;
+
+        loop {
+            let query = continuation
+                .as_deref()
+                .map(|continuation| vec![("continuation", continuation)])
+                .unwrap_or_default();
+
+            let json = self.get(&format!("channels/{}/videos", channel_id), &query)?;
+
+            // Older instances return the videos directly instead of a page
+            let page = match json {
+                Value::Array(_) => InvidiousChannelVideos {
+                    videos: serde_json::from_value(json).map_err(|_| Error::JsonParsing)?,
+                    continuation: None,
+                },
+                json => serde_json::from_value(json).map_err(|_| Error::JsonParsing)?,
+            };
+
+            let empty = page.videos.is_empty();
+            found.extend(page.videos);
+            continuation = page.continuation;
+
+            if empty || continuation.is_none() || found.len() >= skip + count {
+                break;
+            }
+        }
+
+        // Like yt-dlp, only fail when nothing was listed
+        if found.len() <= skip {
+            return Err(Error::VideoParsing);
+        }
+
+        Ok(found
+            .into_iter()
+            .skip(skip)
+            .take(count)
+            .filter_map(|video| video.into_video())
+            .collect())
+    }
+
+    fn try_fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        let id = match channel_id_from_url(url) {
+            Some(id) => id,
+            None => self.get("resolveurl", &[("url", url)])?["ucid"]
+                .as_str()
+                .ok_or(Error::ChannelParsing)?
+                .to_owned(),
+        };
+
+        let json = self.get(&format!("channels/{}", id), &[])?;
+        let name = json["author"].as_str().ok_or(Error::ChannelParsing)?;
+
+        Ok(ChannelInfo {
+            id,
+            name: name.to_owned(),
+            kind: SubscriptionKind::Channel,
+        })
+    }
+
+    fn or_fallback<T>(&self, result: Result<T, Error>, fallback: impl FnOnce() -> T) -> T {
+        result.unwrap_or_else(|err| {
+            log::err(format!(
+                "Invidious instance '{}' failed, falling back to yt-dlp.\nError: {}",
+                self.base_url, err
+            ));
+            fallback()
+        })
+    }
+}
+
+impl VideoSource for Invidious {
+    fn fetch_channel_feed(
+        &self,
+        channel_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.or_fallback(
+            self.try_fetch_channel_feed(channel_id, count, start)
+                .map(Ok),
+            || self.fallback.fetch_channel_feed(channel_id, count, start),
+        )
+    }
+
+    // Streams and shorts always go through yt-dlp
+    fn fetch_channel_tab(
+        &self,
+        channel_id: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        match tab {
+            ChannelTab::Videos => self.fetch_channel_feed(channel_id, count, start),
+            tab => self
+                .fallback
+                .fetch_channel_tab(channel_id, tab, count, start),
+        }
+    }
+
+    fn fetch_video_description(&self, video: &Video) -> Result<String, Error> {
+        let description = self
+            .get(&format!("videos/{}", video.id), &[])
+            .and_then(|json| {
+                json["description"]
+                    .as_str()
+                    .map(|description| Ok(description.to_owned()))
+                    .ok_or(Error::JsonParsing)
+            });
+
+        self.or_fallback(description, || self.fallback.fetch_video_description(video))
+    }
+
+    fn fetch_channels(&self, query: &str, count: usize) -> Result<Vec<ChannelInfo>, Error> {
+        let channels = self
+            .search::<InvidiousChannel>(query, "channel", count)
+            .map(|channels| {
+                channels
+                    .into_iter()
+                    .map(|channel| ChannelInfo {
+                        id: channel.author_id,
+                        name: channel.author,
+                        kind: SubscriptionKind::Channel,
+                    })
+                    .collect()
+            });
+
+        self.or_fallback(channels.map(Ok), || {
+            self.fallback.fetch_channels(query, count)
+        })
+    }
+
+    fn fetch_videos(&self, query: &str, count: usize) -> Result<Vec<VideoInfo>, Error> {
+        let videos = self
+            .search::<InvidiousVideo>(query, "video", count)
+            .map(|videos| {
+                videos
+                    .into_iter()
+                    .filter(|video| !video.is_upcoming)
+                    .filter_map(|video| video.into_video_info())
+                    .collect()
+            });
+
+        self.or_fallback(videos.map(Ok), || self.fallback.fetch_videos(query, count))
+    }
+
+    fn fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        self.or_fallback(self.try_fetch_channel_from_url(url).map(Ok), || {
+            self.fallback.fetch_channel_from_url(url)
+        })
+    }
+
+    // Playlists always go through yt-dlp
+    fn fetch_playlist_feed(
+        &self,
+        playlist_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fallback.fetch_playlist_feed(playlist_id, count, start)
+    }
+
+    fn fetch_playlist_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        self.fallback.fetch_playlist_from_url(url)
+    }
+
+    fn cancel(&self) {
+        self.fallback.cancel();
+    }
+}
+
+impl InvidiousVideo {
+    fn upload(&self) -> Option<DateTime<Local>> {
+        DateTime::from_timestamp(self.published, 0).map(|time| time.with_timezone(&Local))
+    }
+
+    /// Upcoming videos are dated by their scheduled start
+    fn into_video(self) -> Option<Video> {
+        let upload = if self.is_upcoming {
+            DateTime::from_timestamp(self.premiere_timestamp?, 0)
+                .map(|time| time.with_timezone(&Local))?
+        } else {
+            self.upload()?
+        };
+
+        Some(Video {
+            upcoming: self.is_upcoming,
+            duration: self.length_seconds.filter(|_| !self.is_upcoming),
+            view_count: self.view_count.filter(|_| !self.is_upcoming),
+            ..Video::new(
+                self.title,
+                self.video_id,
+                self.description.unwrap_or("N/A".to_owned()),
+                upload,
+            )
+        })
+    }
+
+    fn into_video_info(self) -> Option<VideoInfo> {
+        let upload = self.upload()?;
+        Some(VideoInfo {
+            id: self.video_id,
+            title: self.title,
+            channel: ChannelInfo {
+                id: self.author_id,
+                name: self.author,
+                kind: SubscriptionKind::Channel,
+            },
+            upload,
+            duration: self.length_seconds,
+            view_count: self.view_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::sources::testing::{fake_ytdlp, flat_playlist, Server};
+
+    fn video(id: &str) -> Value {
+        json!({
+            "title": format!("Video {}", id),
+            "videoId": id,
+            "author": "Author",
+            "authorId": "UCauthor",
+            "published": 1_700_000_000,
+            "lengthSeconds": 600,
+            "viewCount": 42,
+        })
+    }
+
+    fn invidious(server: &Server) -> Invidious {
+        let fallback = YtDlp::with_program("/nonexistent/yt-dlp".into(), Duration::from_secs(1));
+        Invidious::new(&format!("{}/", server.url), fallback)
+    }
+
+    #[test]
+    fn channel_videos_follow_continuations() {
+        let server = Server::start(|target| {
+            let page = match target {
+                "/api/v1/channels/UCchannel/videos" => json!({
+                    "videos": [video("a"), video("b"), video("c")],
+                    "continuation": "next",
+                }),
+                "/api/v1/channels/UCchannel/videos?continuation=next" => json!({
+                    "videos": [video("d"), video("e"), video("f")],
+                }),
+                _ => return (404, String::new()),
+            };
+            (200, page.to_string())
+        });
+
+        let videos = invidious(&server)
+            .fetch_channel_feed("UCchannel", 3, Some(3))
+            .unwrap();
+
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(
+            videos
+                .iter()
+                .map(|video| video.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["c", "d", "e"]
+        );
+        assert_eq!(videos[0].duration, Some(600));
+        assert_eq!(videos[0].view_count, Some(42));
+    }
+
+    #[test]
+    fn searches_page_until_enough_results() {
+        let server = Server::start(|target| {
+            let results = if target.contains("type=channel&page=1") {
+                json!([
+                    {"type": "channel", "author": "First", "authorId": "UCfirst"},
+                    {"type": "channel", "author": "Second", "authorId": "UCsecond"},
+                ])
+            } else if target.contains("type=video&page=1") {
+                let mut upcoming = video("soon");
+                upcoming["isUpcoming"] = json!(true);
+                json!([video("a"), upcoming, video("b")])
+            } else {
+                json!([])
+            };
+            (200, results.to_string())
+        });
+        let invidious = invidious(&server);
+
+        let channels = invidious.fetch_channels("query", 5).unwrap();
+        assert_eq!(
+            channels
+                .iter()
+                .map(|channel| (channel.id.as_str(), channel.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("UCfirst", "First"), ("UCsecond", "Second")]
+        );
+
+        let videos = invidious.fetch_videos("query", 5).unwrap();
+        assert_eq!(
+            videos
+                .iter()
+                .map(|video| video.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(videos[0].channel.id, "UCauthor");
+
+        assert!(server
+            .requests()
+            .contains(&"/api/v1/search?q=query&type=video&page=2".to_owned()));
+    }
+
+    #[test]
+    fn description_is_fetched_from_the_video() {
+        let server = Server::start(|target| match target {
+            "/api/v1/videos/abc" => (200, json!({"description": "Hello"}).to_string()),
+            _ => (404, String::new()),
+        });
+        let video = Video::new("Title", "abc", "N/A", Local::now());
+
+        assert_eq!(
+            invidious(&server).fetch_video_description(&video).unwrap(),
+            "Hello"
+        );
+    }
+
+    #[test]
+    fn instance_errors_fall_back_to_ytdlp() {
+        let server = Server::start(|_| (500, "Internal Server Error".to_owned()));
+
+        let feed = Invidious::new(&server.url, fake_ytdlp(&flat_playlist(2)))
+            .fetch_channel_feed("UCchannel", 10, None)
+            .unwrap();
+        assert_eq!(feed.len(), 2);
+        assert_eq!(feed[0].id, "v00");
+
+        let description = Invidious::new(
+            &server.url,
+            fake_ytdlp(&json!({"description": "From yt-dlp"}).to_string()),
+        )
+        .fetch_video_description(&feed[0])
+        .unwrap();
+        assert_eq!(description, "From yt-dlp");
+
+        assert_eq!(server.requests().len(), 2);
+    }
+}
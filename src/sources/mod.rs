@@ -0,0 +1,103 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    view::Error,
+    yt::{ChannelInfo, ChannelTab, SubscriptionKind, Video, VideoInfo},
+};
+
+pub mod fixture;
+pub mod invidious;
+pub mod rss;
+#[cfg(test)]
+pub mod testing;
+pub mod ytdlp;
+
+/// Where channel feeds, searches and descriptions are fetched from
+pub trait VideoSource: Send + Sync {
+    fn fetch_channel_feed(
+        &self,
+        channel_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error>;
+
+    /// Lists the videos of one of a channel's tabs, shorts included when asked for
+    fn fetch_channel_tab(
+        &self,
+        channel_id: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error>;
+
+    fn fetch_video_description(&self, video: &Video) -> Result<String, Error>;
+
+    fn fetch_channels(&self, query: &str, count: usize) -> Result<Vec<ChannelInfo>, Error>;
+
+    fn fetch_videos(&self, query: &str, count: usize) -> Result<Vec<VideoInfo>, Error>;
+
+    fn fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error>;
+
+    fn fetch_playlist_feed(
+        &self,
+        playlist_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error>;
+
+    fn fetch_playlist_from_url(&self, url: &str) -> Result<ChannelInfo, Error>;
+
+    /// Kills any fetches still running and fails those started afterwards. Sources are built for each refresh,
+    /// so this only ever ends the refresh being cancelled
+    fn cancel(&self) {}
+
+    /// Fetches the feed of any kind of subscription
+    fn fetch_feed(
+        &self,
+        kind: SubscriptionKind,
+        id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        match kind {
+            SubscriptionKind::Channel => self.fetch_channel_feed(id, count, start),
+            SubscriptionKind::Playlist => self.fetch_playlist_feed(id, count, start),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+    #[default]
+    YtDlp,
+    Rss,
+    Invidious,
+    Fixture,
+}
+
+pub fn from_config(config: &Config) -> Arc<dyn VideoSource> {
+    let ytdlp = ytdlp::YtDlp::new(Duration::from_secs(config.refresh_timeout));
+
+    match config.source {
+        SourceKind::YtDlp => Arc::new(ytdlp),
+        SourceKind::Rss => Arc::new(rss::Rss::new(&config.rss_url, ytdlp)),
+        SourceKind::Invidious => Arc::new(invidious::Invidious::new(&config.invidious_url, ytdlp)),
+        SourceKind::Fixture => Arc::new(fixture::Fixture::new(&config.fixture_directory)),
+    }
+}
+
+pub fn http_get(url: &str, query: &[(&str, &str)]) -> Result<String, Error> {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(20))
+        .build()
+        .get(url)
+        .query_pairs(query.iter().copied())
+        .call()
+        .map_err(|e| Error::RequestFailed(e.to_string()))?
+        .into_string()
+        .map_err(|e| Error::RequestFailed(e.to_string()))
+}
@@ -0,0 +1,296 @@
+use chrono::{DateTime, Local};
+
+use crate::{
+    view::Error,
+    yt::{ChannelInfo, ChannelTab, Video, VideoInfo},
+};
+
+use super::{http_get, ytdlp::YtDlp, VideoSource};
+
+/// Reads the latest uploads from YouTube's channel feeds, which is much faster than yt-dlp
+/// but only holds the last 15 uploads. Everything else, playlists included, falls back to yt-dlp.
+pub struct Rss {
+    base_url: String,
+    fallback: YtDlp,
+}
+
+impl Rss {
+    pub fn new(base_url: &str, fallback: YtDlp) -> Rss {
+        Rss {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            fallback,
+        }
+    }
+}
+
+impl VideoSource for Rss {
+    fn fetch_channel_feed(
+        &self,
+        channel_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        if start.is_some() {
+            return self.fallback.fetch_channel_feed(channel_id, count, start);
+        }
+
+        let raw = http_get(
+            &format!("{}/feeds/videos.xml", self.base_url),
+            &[("channel_id", channel_id)],
+        )?;
+
+        let mut videos = parse_feed(&raw)?;
+        videos.truncate(count);
+        Ok(videos)
+    }
+
+    fn fetch_channel_tab(
+        &self,
+        channel_id: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fallback
+            .fetch_channel_tab(channel_id, tab, count, start)
+    }
+
+    fn fetch_video_description(&self, video: &Video) -> Result<String, Error> {
+        self.fallback.fetch_video_description(video)
+    }
+
+    fn fetch_channels(&self, query: &str, count: usize) -> Result<Vec<ChannelInfo>, Error> {
+        self.fallback.fetch_channels(query, count)
+    }
+
+    fn fetch_videos(&self, query: &str, count: usize) -> Result<Vec<VideoInfo>, Error> {
+        self.fallback.fetch_videos(query, count)
+    }
+
+    fn fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        self.fallback.fetch_channel_from_url(url)
+    }
+
+    // Playlist feeds only hold their first 15 items, which never change when items are added to the end
+    fn fetch_playlist_feed(
+        &self,
+        playlist_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fallback.fetch_playlist_feed(playlist_id, count, start)
+    }
+
+    fn fetch_playlist_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        self.fallback.fetch_playlist_from_url(url)
+    }
+
+    fn cancel(&self) {
+        self.fallback.cancel();
+    }
+}
+
+/// Parses a channel's Atom feed into videos, leaving out shorts
+pub fn parse_feed(raw: &str) -> Result<Vec<Video>, Error> {
+    let document = roxmltree::Document::parse(raw).map_err(|_| Error::XmlParsing)?;
+
+    let videos: Vec<Video> = document
+        .descendants()
+        .filter(|node| node.has_tag_name(("http://www.w3.org/2005/Atom", "entry")))
+        .filter_map(|entry| {
+            let child_text = |name: &str| {
+                entry
+                    .descendants()
+                    .find(|node| node.tag_name().name() == name)
+                    .and_then(|node| node.text())
+            };
+
+            let is_short = entry
+                .children()
+                .filter(|node| node.tag_name().name() == "link")
+                .filter_map(|link| link.attribute("href"))
+                .any(|href| href.contains("/shorts/"));
+
+            if is_short {
+                return None;
+            }
+
+            let upload = DateTime::parse_from_rfc3339(child_text("published")?)
+                .ok()?
+                .with_timezone(&Local);
+
+            let view_count = entry
+                .descendants()
+                .find(|node| node.tag_name().name() == "statistics")
+                .and_then(|node| node.attribute("views"))
+                .and_then(|views| views.parse().ok());
+
+            Some(Video {
+                view_count,
+                ..Video::new(
+                    child_text("title")?,
+                    child_text("videoId")?,
+                    child_text("description").unwrap_or("N/A"),
+                    upload,
+                )
+            })
+        })
+        .collect();
+
+    if videos.is_empty() {
+        Err(Error::VideoParsing)
+    } else {
+        Ok(videos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sources::testing::{fake_ytdlp, flat_playlist, Server};
+
+    fn entry(id: &str, title: &str, link: &str, views: Option<u64>) -> String {
+        let statistics = views
+            .map(|views| {
+                format!(
+                    "<media:community><media:statistics views=\"{}\"/></media:community>",
+                    views
+                )
+            })
+            .unwrap_or_default();
+
+        format!(
+            "<entry>\
+                <id>yt:video:{id}</id>\
+                <yt:videoId>{id}</yt:videoId>\
+                <title>{title}</title>\
+                <link rel=\"alternate\" href=\"{link}\"/>\
+                <published>2024-05-01T12:00:00+00:00</published>\
+                <media:group>\
+                    <media:title>{title}</media:title>\
+                    <media:description>About {title}</media:description>\
+                    {statistics}\
+                </media:group>\
+            </entry>"
+        )
+    }
+
+    fn feed(entries: &[String]) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <feed xmlns:yt=\"http://www.youtube.com/xml/schemas/2015\" \
+                xmlns:media=\"http://search.yahoo.com/mrss/\" \
+                xmlns=\"http://www.w3.org/2005/Atom\">\
+                <title>Channel</title>\
+                {}\
+            </feed>",
+            entries.concat()
+        )
+    }
+
+    fn rss(server: &Server, fallback: YtDlp) -> Rss {
+        Rss::new(&format!("{}/", server.url), fallback)
+    }
+
+    fn unused_fallback() -> YtDlp {
+        YtDlp::with_program("/nonexistent/yt-dlp".into(), Duration::from_secs(1))
+    }
+
+    #[test]
+    fn channel_feed_is_parsed_without_shorts() {
+        let body = feed(&[
+            entry(
+                "aaa",
+                "First",
+                "https://www.youtube.com/watch?v=aaa",
+                Some(1234),
+            ),
+            entry(
+                "sss",
+                "Short",
+                "https://www.youtube.com/shorts/sss",
+                Some(99),
+            ),
+            entry("bbb", "Second", "https://www.youtube.com/watch?v=bbb", None),
+        ]);
+        let server = Server::start(move |_| (200, body.clone()));
+
+        let videos = rss(&server, unused_fallback())
+            .fetch_channel_feed("UCchannel", 15, None)
+            .unwrap();
+
+        assert_eq!(
+            server.requests(),
+            vec!["/feeds/videos.xml?channel_id=UCchannel"]
+        );
+        assert_eq!(
+            videos
+                .iter()
+                .map(|video| video.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["aaa", "bbb"]
+        );
+        assert_eq!(videos[0].title, "First");
+        assert_eq!(videos[0].description, "About First");
+        assert_eq!(videos[0].view_count, Some(1234));
+        assert_eq!(videos[1].view_count, None);
+    }
+
+    #[test]
+    fn channel_feed_is_truncated_to_the_count() {
+        let body = feed(
+            &(0..15)
+                .map(|index| {
+                    let id = format!("id{}", index);
+                    entry(
+                        &id,
+                        &id,
+                        &format!("https://www.youtube.com/watch?v={}", id),
+                        None,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+        let server = Server::start(move |_| (200, body.clone()));
+
+        let videos = rss(&server, unused_fallback())
+            .fetch_channel_feed("UCchannel", 5, None)
+            .unwrap();
+
+        assert_eq!(videos.len(), 5);
+        assert_eq!(videos[0].id, "id0");
+    }
+
+    #[test]
+    fn paging_with_a_start_falls_back_to_ytdlp() {
+        let server = Server::start(|_| (200, feed(&[])));
+
+        let videos = rss(&server, fake_ytdlp(&flat_playlist(3)))
+            .fetch_channel_feed("UCchannel", 10, Some(16))
+            .unwrap();
+
+        assert!(server.requests().is_empty());
+        assert_eq!(videos.len(), 3);
+        assert_eq!(videos[0].id, "v00");
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let server = Server::start(|target| match target.contains("UCgone") {
+            true => (404, "Not Found".to_owned()),
+            false => (200, "not xml".to_owned()),
+        });
+        let rss = rss(&server, unused_fallback());
+
+        assert!(matches!(
+            rss.fetch_channel_feed("UCgone", 15, None),
+            Err(Error::RequestFailed(_))
+        ));
+        assert!(matches!(
+            rss.fetch_channel_feed("UCbroken", 15, None),
+            Err(Error::XmlParsing)
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_json::json;
+
+use super::ytdlp::YtDlp;
+
+/// A fresh directory under the system's temp directory, unique to this test run
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "yt-feeds-{}-{}-{}",
+        name,
+        process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// One line of yt-dlp's `--flat-playlist --dump-json` output
+pub fn flat_entry(id: &str, title: &str, timestamp: i64) -> String {
+    json!({
+        "id": id,
+        "title": title,
+        "timestamp": timestamp,
+        "description": null,
+        "availability": null,
+        "url": format!("https://www.youtube.com/watch?v={}", id),
+    })
+    .to_string()
+}
+
+/// A flat playlist of `count` videos with IDs `v00`, `v01`, ..., newest first
+pub fn flat_playlist(count: usize) -> String {
+    (0..count)
+        .map(|index| {
+            flat_entry(
+                &format!("v{:02}", index),
+                &format!("Video {}", index),
+                1_700_000_000 - index as i64 * 3600,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// A local HTTP server answering every request with the status and body `respond` gives for its path and query
+pub struct Server {
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    pub fn start(respond: impl Fn(&str) -> (u16, String) + Send + 'static) -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+
+                // Skip the headers, as requests never have a body
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    header.clear();
+                }
+
+                let target = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_owned();
+                let (status, body) = respond(&target);
+                received.lock().unwrap().push(target);
+
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Canned\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+
+        Server { url, requests }
+    }
+
+    /// The path and query of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// A yt-dlp stand-in that prints `output` whatever it is asked for
+pub fn fake_ytdlp(output: &str) -> YtDlp {
+    fake_ytdlp_script(&format!("cat <<'CANNED'\n{}\nCANNED", output))
+}
+
+/// A yt-dlp stand-in running a shell script
+pub fn fake_ytdlp_script(script: &str) -> YtDlp {
+    let program = temp_dir("yt-dlp").join("yt-dlp");
+    fs::write(&program, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    YtDlp::with_program(program, Duration::from_secs(10))
+}
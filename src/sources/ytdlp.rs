@@ -0,0 +1,284 @@
+use std::{
+    io::Read,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use crate::{
+    search::{parse_channel, parse_channels, parse_playlist, parse_videos},
+    view::Error,
+    yt::{parse_channel_tab, ChannelInfo, ChannelTab, Video, VideoInfo},
+};
+
+use super::VideoSource;
+
+pub struct YtDlp {
+    program: PathBuf,
+    timeout: Duration,
+    cancelled: AtomicBool,
+}
+
+impl YtDlp {
+    /// Channel feeds taking longer than `timeout` have their yt-dlp process killed
+    pub fn new(timeout: Duration) -> YtDlp {
+        YtDlp {
+            program: PathBuf::from("yt-dlp"),
+            timeout,
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// Runs a stand-in for yt-dlp instead of the installed one
+    #[cfg(test)]
+    pub fn with_program(program: PathBuf, timeout: Duration) -> YtDlp {
+        YtDlp {
+            program,
+            timeout,
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// Lists `count` items of a channel tab or playlist from the 1-based `start` with `--flat-playlist --dump-json`
+    fn fetch_flat_playlist(
+        &self,
+        url: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        let output = output_with_timeout(
+            Command::new(&self.program)
+                .arg("--playlist-items")
+                // Both ends of the range are included
+                .arg(format!(
+                    "{}:{}",
+                    start.unwrap_or(1),
+                    start.unwrap_or(1) + count - 1
+                ))
+                .arg("--flat-playlist")
+                .arg("--dump-json")
+                .arg(url)
+                .arg("--extractor-args")
+                .arg("youtubetab:approximate_date"),
+            self.timeout,
+            &self.cancelled,
+        )?;
+
+        // Prefer the reason yt-dlp gives over a generic parsing error
+        parse_channel_tab(&String::from_utf8_lossy(&output.stdout), tab).map_err(|err| {
+            stderr_error(&output.stderr)
+                .map(Error::CommandFailed)
+                .unwrap_or(err)
+        })
+    }
+
+    /// Describes the channel or playlist at a URL without listing its items
+    fn fetch_single_json(&self, url: &str) -> Result<String, Error> {
+        let output = output_with_timeout(
+            Command::new(&self.program)
+                .arg("--flat-playlist")
+                .arg("--dump-single-json")
+                .arg("--playlist-items")
+                .arg("0")
+                .arg(url),
+            self.timeout,
+            &self.cancelled,
+        )?;
+
+        if !output.status.success() {
+            return Err(Error::CommandFailed(
+                stderr_error(&output.stderr)
+                    .unwrap_or_else(|| format!("yt-dlp exited with {}", output.status)),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Runs a command to completion, killing it if it outlives the timeout or is cancelled
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+    cancelled: &AtomicBool,
+) -> Result<Output, Error> {
+    if cancelled.load(Ordering::Relaxed) {
+        return Err(Error::CommandFailed("Refresh was cancelled".to_owned()));
+    }
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::CommandFailed(e.to_string()))?;
+
+    // Drain pipes while waiting so a full buffer can not stall the process
+    let stdout = child.stdout.take().map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    });
+    let stderr = child.stderr.take().map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if cancelled.load(Ordering::Relaxed) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::CommandFailed("Refresh was cancelled".to_owned()));
+            }
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::CommandFailed(format!(
+                    "yt-dlp timed out after {} seconds",
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(Error::CommandFailed(e.to_string())),
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default(),
+        stderr: stderr
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// The first error yt-dlp reported, without its "ERROR: [extractor] id:" prefix
+fn stderr_error(stderr: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .find_map(|line| line.strip_prefix("ERROR: "))
+        .map(|error| {
+            error
+                .strip_prefix('[')
+                .and_then(|tagged| tagged.split_once(": "))
+                .map(|(_, reason)| reason)
+                .unwrap_or(error)
+                .to_owned()
+        })
+}
+
+impl VideoSource for YtDlp {
+    fn fetch_channel_feed(
+        &self,
+        channel: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fetch_channel_tab(channel, ChannelTab::Videos, count, start)
+    }
+
+    fn fetch_channel_tab(
+        &self,
+        channel_id: &str,
+        tab: ChannelTab,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fetch_flat_playlist(
+            &format!(
+                "https://www.youtube.com/channel/{}/{}",
+                channel_id,
+                tab.path()
+            ),
+            tab,
+            count,
+            start,
+        )
+    }
+
+    fn fetch_video_description(&self, video: &Video) -> Result<String, Error> {
+        let output = Command::new(&self.program)
+            .arg("--dump-json")
+            .arg(video.url())
+            .output()
+            .map_err(|e| Error::CommandFailed(e.to_string()))?;
+
+        let json_raw = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&json_raw)
+            .map_err(|_| {
+                stderr_error(&output.stderr)
+                    .map(Error::CommandFailed)
+                    .unwrap_or(Error::JsonParsing)
+            })
+            .and_then(|json: Value| {
+                json["description"]
+                    .as_str()
+                    .ok_or(Error::JsonParsing)
+                    .map(|str| str.to_owned())
+            })
+    }
+
+    fn fetch_channels(&self, channel: &str, queries: usize) -> Result<Vec<ChannelInfo>, Error> {
+        let output = Command::new(&self.program)
+            .arg("--flat-playlist")
+            .arg("--dump-json")
+            .arg(format!("ytsearch{}:{}", queries, channel))
+            .output()
+            .map_err(|e| Error::CommandFailed(e.to_string()))?;
+
+        Ok(parse_channels(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn fetch_videos(&self, query: &str, queries: usize) -> Result<Vec<VideoInfo>, Error> {
+        let output = Command::new(&self.program)
+            .arg("--flat-playlist")
+            .arg("--dump-json")
+            .arg("--extractor-args")
+            .arg("youtubetab:approximate_date")
+            .arg(format!("ytsearch{}:{}", queries, query))
+            .output()
+            .map_err(|e| Error::CommandFailed(e.to_string()))?;
+
+        Ok(parse_videos(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn fetch_channel_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        parse_channel(&self.fetch_single_json(url)?)
+    }
+
+    fn fetch_playlist_feed(
+        &self,
+        playlist_id: &str,
+        count: usize,
+        start: Option<usize>,
+    ) -> Result<Vec<Video>, Error> {
+        self.fetch_flat_playlist(
+            &format!("https://www.youtube.com/playlist?list={}", playlist_id),
+            ChannelTab::Videos,
+            count,
+            start,
+        )
+    }
+
+    fn fetch_playlist_from_url(&self, url: &str) -> Result<ChannelInfo, Error> {
+        parse_playlist(&self.fetch_single_json(url)?)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
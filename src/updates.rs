@@ -1,107 +1,808 @@
 use std::{
     cmp::min,
-    sync::mpsc::{Receiver, Sender},
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
     thread,
     time::Duration,
 };
 
-use crossterm::style::Stylize;
+use chrono::Local;
+use crossterm::{
+    event::{self, KeyCode},
+    style::Stylize,
+    terminal,
+};
 
+use crate::sources::VideoSource;
 use crate::{
-    clear_screen, log,
-    yt::{ChannelInfo, Channels},
+    clear_screen,
+    filters::Filters,
+    log,
+    progress::ProgressStore,
+    view::Error,
+    yt::{Channel, ChannelInfo, ChannelTab, Channels, SubscriptionKind, Video},
 };
-use crate::{yt, Channel};
 
 #[derive(PartialEq, Eq)]
 pub enum Blocking {
-    WaitForN(usize),
+    Wait,
     NoWait,
 }
 
-pub fn fetch_updates(tx: Sender<Channel>, channels: Vec<ChannelInfo>, video_count: usize) {
-    channels.into_iter().for_each(|channel| {
+/// Videos fetched per page while looking for the newest already known video
+const PAGE_SIZE: usize = 10;
+
+/// A channel to refresh along with the video IDs it already has in each tab
+pub struct RefreshTarget {
+    pub channel: ChannelInfo,
+    pub known: HashMap<ChannelTab, HashSet<String>>,
+    /// Known videos cached without a length or view count, which a refresh fills in as it pages past them
+    pub missing_details: HashSet<String>,
+}
+
+impl From<&Channel> for RefreshTarget {
+    fn from(value: &Channel) -> Self {
+        RefreshTarget {
+            channel: value.into(),
+            known: ChannelTab::ALL
+                .into_iter()
+                .map(|tab| (tab, value.known_ids(tab)))
+                .collect(),
+            missing_details: value
+                .videos
+                .iter()
+                .filter(|video| video.details().is_none())
+                .map(|video| video.id.clone())
+                .collect(),
+        }
+    }
+}
+
+impl From<ChannelInfo> for RefreshTarget {
+    fn from(value: ChannelInfo) -> Self {
+        RefreshTarget {
+            channel: value,
+            known: HashMap::new(),
+            missing_details: HashSet::new(),
+        }
+    }
+}
+
+/// The outcome of fetching a single channel's feed
+pub struct Update {
+    pub channel: ChannelInfo,
+    pub result: Result<Vec<Video>, Error>,
+}
+
+/// A channel that refreshed successfully and how many videos it gained
+pub struct ChannelRefresh {
+    pub channel: ChannelInfo,
+    pub new_videos: usize,
+}
+
+/// A channel that could not be refreshed
+pub struct RefreshFailure {
+    pub channel: ChannelInfo,
+    pub reason: String,
+}
+
+/// Per-channel results of a refresh
+#[derive(Default)]
+pub struct RefreshReport {
+    pub updated: Vec<ChannelRefresh>,
+    pub failed: Vec<RefreshFailure>,
+    /// Channels never fetched because the refresh was cancelled
+    pub skipped: usize,
+}
+
+impl RefreshReport {
+    pub fn new_videos(&self) -> usize {
+        self.updated.iter().map(|update| update.new_videos).sum()
+    }
+
+    /// Channels unsubscribed from during the refresh are left out
+    fn add(&mut self, channel: ChannelInfo, result: Result<Option<usize>, Error>) {
+        match result {
+            Ok(Some(new_videos)) => self.updated.push(ChannelRefresh {
+                channel,
+                new_videos,
+            }),
+            Ok(None) => (),
+            Err(err) => self.failed.push(RefreshFailure {
+                channel,
+                reason: err.to_string(),
+            }),
+        }
+    }
+}
+
+/// An in-progress refresh, receiving one update per channel from its worker pool
+pub struct Refresh {
+    rx: Receiver<Update>,
+    source: Arc<dyn VideoSource>,
+    cancelled: Arc<AtomicBool>,
+    pending: usize,
+}
+
+impl Refresh {
+    /// Stops workers from picking up any more channels and kills the fetches they are running
+    pub fn cancel(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.source.cancel();
+        self.pending = 0;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Returns the next finished update, or None once every channel is accounted for.
+    /// When not blocking, also returns None if no update is ready yet
+    pub fn next_update(&mut self, block: bool) -> Option<Update> {
+        if self.is_done() {
+            return None;
+        }
+
+        let update = if block {
+            self.rx.recv().ok()
+        } else {
+            self.rx.try_recv().ok()
+        };
+
+        if update.is_some() {
+            self.pending -= 1;
+        }
+        update
+    }
+}
+
+/// Fetches each channel's feed, along with any extra `tabs` of channel subscriptions, on a pool of at most `workers` threads
+pub fn fetch_updates(
+    source: Arc<dyn VideoSource>,
+    channels: Vec<RefreshTarget>,
+    tabs: &[ChannelTab],
+    video_count: usize,
+    workers: usize,
+) -> Refresh {
+    let (tx, rx) = mpsc::channel::<Update>();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let pending = channels.len();
+    let workers = min(workers.max(1), channels.len());
+    // Workers pop from the back, so reverse to refresh in subscription order
+    let queue = Arc::new(Mutex::new(channels.into_iter().rev().collect::<Vec<_>>()));
+
+    for _ in 0..workers {
         let tx = tx.clone();
-        thread::spawn(move || {
-            let feed = yt::fetch_channel_feed(&channel.id, video_count, None);
-            match feed {
-                Ok(feed) => {
-                    tx.send(Channel::new(channel.name, channel.id, feed))
-                        .unwrap();
-                }
-                Err(err) => {
-                    log::err(format!("Could not load in feed for channel: '{}' with command 'yt-dlp'.\nError: {}", channel.id, err));
-                },
+        let source = Arc::clone(&source);
+        let queue = Arc::clone(&queue);
+        let cancelled = Arc::clone(&cancelled);
+        let tabs = tabs.to_vec();
+
+        thread::spawn(move || loop {
+            if cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let Some(target) = queue.lock().unwrap().pop() else {
+                return;
+            };
+
+            let result = fetch_channel(source.as_ref(), &target, &tabs, video_count);
+            let channel = target.channel;
+            if let Err(err) = &result {
+                log::err(format!(
+                    "Could not load in feed for channel: '{}'.\nError: {}",
+                    channel.id, err
+                ));
+            }
+
+            // Receiver is gone once the refresh is cancelled or dropped
+            if tx.send(Update { channel, result }).is_err() {
+                return;
             }
         });
-    });
+    }
+
+    Refresh {
+        rx,
+        source,
+        cancelled,
+        pending,
+    }
+}
+
+/// Fetches the new videos of a channel's feed and its extra tabs.
+/// A failing extra tab is only logged so it never hides the channel's regular uploads
+fn fetch_channel(
+    source: &dyn VideoSource,
+    target: &RefreshTarget,
+    tabs: &[ChannelTab],
+    video_count: usize,
+) -> Result<Vec<Video>, Error> {
+    let mut videos = fetch_new_videos(source, target, ChannelTab::Videos, video_count)?;
+
+    if target.channel.kind != SubscriptionKind::Channel {
+        return Ok(videos);
+    }
+
+    for tab in tabs.iter().filter(|tab| **tab != ChannelTab::Videos) {
+        match fetch_new_videos(source, target, *tab, video_count) {
+            Ok(tab_videos) => videos.extend(tab_videos),
+            Err(err) => log::err(format!(
+                "Could not load {} tab for channel: '{}'.\nError: {}",
+                tab.name(),
+                target.channel.id,
+                err
+            )),
+        }
+    }
+
+    Ok(videos)
+}
+
+/// Pages through a channel tab's uploads until reaching a video that is already known. The known videos on those
+/// pages are kept so their view counts can be updated. Channels with no known videos are fetched up to
+/// `video_count`. Playlists usually grow at their end, so they are also paged from where their known items end
+fn fetch_new_videos(
+    source: &dyn VideoSource,
+    target: &RefreshTarget,
+    tab: ChannelTab,
+    video_count: usize,
+) -> Result<Vec<Video>, Error> {
+    let ChannelInfo { id, kind, .. } = &target.channel;
+    let fetch = |count: usize, start: Option<usize>| match tab {
+        ChannelTab::Videos => source.fetch_feed(*kind, id, count, start),
+        tab => source.fetch_channel_tab(id, tab, count, start),
+    };
+
+    // Each tab is compared against its own videos, so a newly enabled tab is fetched in full
+    let known = target.known.get(&tab).cloned().unwrap_or_default();
+    if known.is_empty() {
+        return fetch(video_count, None);
+    }
+
+    let mut videos = fetch_until_known(&fetch, &known, &target.missing_details, video_count)?;
+    if *kind == SubscriptionKind::Playlist {
+        fetch_playlist_end(&fetch, &known, &mut videos, video_count)?;
+    }
+
+    Ok(videos)
+}
+
+/// Collects videos from the start of a feed until a page reaches a known video, the feed ends or `video_count`
+/// videos are found. Paging carries on past known videos while it fills in details missing from the cache
+fn fetch_until_known(
+    fetch: &impl Fn(usize, Option<usize>) -> Result<Vec<Video>, Error>,
+    known: &HashSet<String>,
+    missing_details: &HashSet<String>,
+    video_count: usize,
+) -> Result<Vec<Video>, Error> {
+    let reaches_known = |page: &[Video]| {
+        let backfills = page
+            .iter()
+            .any(|video| missing_details.contains(&video.id) && video.details().is_some());
+        !backfills && page.iter().any(|video| known.contains(&video.id))
+    };
+
+    // Sources such as RSS have a cheap latest feed, which is all that is needed when it reaches a known video
+    let latest = fetch(PAGE_SIZE, None)?;
+    if reaches_known(&latest) {
+        return Ok(latest);
+    }
+
+    // Otherwise page from the first item, as the latest feed need not line up with the pages.
+    // Shorts and unavailable videos are left out of pages, so pages advance by how many items were asked for
+    let mut videos = Vec::new();
+    let mut start = 1;
+
+    loop {
+        let page = match fetch(PAGE_SIZE, Some(start)) {
+            Ok(page) => page,
+            // Paged past the end of the channel
+            Err(Error::VideoParsing) => return Ok(videos),
+            Err(err) => return Err(err),
+        };
+        let reached_known = reaches_known(&page);
 
-    drop(tx);
+        videos.extend(page);
+
+        if reached_known || videos.len() >= video_count {
+            return Ok(videos);
+        }
+
+        start += PAGE_SIZE;
+    }
 }
 
-pub fn check_updates(rx: &Receiver<Channel>, channels: &mut Channels, blocking: Blocking) {
+/// Pages on from where a playlist's known items end, starting a page early in case any were removed, and adds
+/// unknown items until the playlist ends or `video_count` new videos are found
+fn fetch_playlist_end(
+    fetch: &impl Fn(usize, Option<usize>) -> Result<Vec<Video>, Error>,
+    known: &HashSet<String>,
+    videos: &mut Vec<Video>,
+    video_count: usize,
+) -> Result<(), Error> {
+    let mut new = videos
+        .iter()
+        .filter(|video| !known.contains(&video.id))
+        .count();
+    let mut start = (known.len() + new + 1).saturating_sub(PAGE_SIZE).max(1);
+
+    while new < video_count {
+        let page = match fetch(PAGE_SIZE, Some(start)) {
+            Ok(page) => page,
+            // Paged past the end of the playlist
+            Err(Error::VideoParsing) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        for video in page {
+            if !known.contains(&video.id) && !videos.iter().any(|other| other.id == video.id) {
+                videos.push(video);
+                new += 1;
+            }
+        }
+
+        start += PAGE_SIZE;
+    }
+
+    Ok(())
+}
+
+pub fn check_updates(
+    refresh: &mut Refresh,
+    channels: &mut Channels,
+    progress: &ProgressStore,
+    filters: &Filters,
+    blocking: Blocking,
+) -> RefreshReport {
+    let mut report = RefreshReport::default();
+
     match blocking {
-        Blocking::WaitForN(number) => {
-            let mut updated = 0;
+        Blocking::Wait => {
+            let total = refresh.pending;
             let mut step = 0;
             let steps = ["⢿", "⣻", "⣽", "⣾", "⣷", "⣯", "⣟", "⡿"];
 
-            // block till specified number of channels are refreshed
-            // TODO: ensure optional auto refresh on startup is done - if not, updated channel may be from old update
-            while updated < min(number, channels.len()) {
-                if let Ok(mut fetched) = rx.try_recv() {
-                    // queue of updates more than channels exist (old stacked updates) -> give fake refresh time and return
-                    if updated >= channels.len() {
-                        thread::sleep(Duration::from_secs(1));
-                        return;
-                    }
-
-                    if let Some(existing) = channels.channel_by_id_mut(&fetched.id) {
-                        for new_video in &fetched.videos {
-                            if !existing
-                                .videos
-                                .iter()
-                                .any(|existing_video| existing_video.id == new_video.id)
-                            {
-                                existing.videos.push(new_video.clone());
-                            }
-                        }
-                        fetched.videos.sort_by(|a, b| b.upload.cmp(&a.upload));
-                        updated += 1;
-                    }
+            terminal::enable_raw_mode().unwrap();
+
+            // block till every channel is refreshed or the user cancels
+            while !refresh.is_done() {
+                while let Some(update) = refresh.next_update(false) {
+                    let channel = update.channel.clone();
+                    report.add(channel, merge_update(channels, update, progress, filters));
                 }
 
                 clear_screen();
-                println!("{}", "\nRefreshing Channels\n".cyan().bold());
-                println!(
-                    "{} {}  {}\n",
+                print!("\r\n{}\r\n\r\n", "Refreshing Channels".cyan().bold());
+                print!(
+                    "{} {}{}  {}\r\n",
                     "Channels Updated:".green(),
-                    updated.to_string().yellow(),
+                    report.updated.len().to_string().yellow(),
+                    format!("/{}", total).yellow(),
                     steps[step]
                 );
+                print!("\r\n{}\r\n", "Options: [(c)ancel]".green().italic());
 
-                step += 1;
-                if step > steps.len() - 1 {
-                    step = 0;
+                step = (step + 1) % steps.len();
+
+                if event::poll(Duration::from_millis(450)).unwrap()
+                    && let event::Event::Key(key_event) = event::read().unwrap()
+                    && key_event.code == KeyCode::Char('c')
+                {
+                    report.skipped = refresh.pending;
+                    refresh.cancel();
                 }
-                thread::sleep(Duration::from_millis(450));
             }
+
+            terminal::disable_raw_mode().unwrap();
         }
         Blocking::NoWait => {
-            while let Ok(mut fetched) = rx.try_recv() {
-                if let Some(existing) = channels.channel_by_id_mut(&fetched.id) {
-                    for new_video in &fetched.videos {
-                        if !existing
-                            .videos
-                            .iter()
-                            .any(|existing_video| existing_video.id == new_video.id)
-                        {
-                            existing.videos.push(new_video.clone());
-                        }
-                    }
-                    fetched.videos.sort_by(|a, b| b.upload.cmp(&a.upload));
-                }
+            while let Some(update) = refresh.next_update(false) {
+                let channel = update.channel.clone();
+                report.add(channel, merge_update(channels, update, progress, filters));
             }
         }
     }
+
+    report
+}
+
+/// Adds any newly fetched videos to their existing channel and records the refresh, returning how many were new.
+/// Updates for channels that are no longer subscribed are ignored and return None
+pub fn merge_update(
+    channels: &mut Channels,
+    update: Update,
+    progress: &ProgressStore,
+    filters: &Filters,
+) -> Result<Option<usize>, Error> {
+    let Some(existing) = channels.channel_by_id_mut(&update.channel.id) else {
+        return Ok(None);
+    };
+
+    existing.refresh.last_refreshed = Some(Local::now());
+
+    match update.result {
+        Ok(videos) => {
+            existing.refresh.last_error = None;
+            // Anything fetched by a refresh that the channel did not have yet is unread
+            let videos = videos
+                .into_iter()
+                .map(|video| Video {
+                    seen: false,
+                    ..video
+                })
+                .collect();
+            Ok(Some(existing.add_videos(videos, progress, filters)))
+        }
+        Err(err) => {
+            existing.refresh.last_error = Some(err.to_string());
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{DateTime, Local};
+
+    use super::*;
+    use crate::mpv::WatchProgress;
+    use crate::sources::{
+        fixture::Fixture,
+        testing::{fake_ytdlp_script, flat_entry, flat_playlist, temp_dir},
+    };
+
+    fn fixture(channels: &[(&str, usize)]) -> Arc<dyn VideoSource> {
+        let root = temp_dir("fixture");
+        fs::create_dir_all(root.join("channels")).unwrap();
+        for (id, count) in channels {
+            fs::write(
+                root.join("channels").join(format!("{}.jsonl", id)),
+                flat_playlist(*count),
+            )
+            .unwrap();
+        }
+        Arc::new(Fixture::new(root.to_str().unwrap()))
+    }
+
+    fn video(id: &str) -> Video {
+        Video::new(id, id, "N/A", DateTime::<Local>::MIN_UTC.into())
+    }
+
+    fn merge(channels: &mut Channels, update: Update) -> Result<usize, Error> {
+        merge_update(
+            channels,
+            update,
+            &ProgressStore::default(),
+            &Filters::default(),
+        )
+        .map(|added| added.expect("channel is subscribed"))
+    }
+
+    fn refresh_all(source: Arc<dyn VideoSource>, channels: &Channels) -> Vec<Update> {
+        let targets = channels.iter().map(RefreshTarget::from).collect();
+        let mut refresh = fetch_updates(source, targets, &[ChannelTab::Videos], 20, 2);
+        std::iter::from_fn(|| refresh.next_update(true)).collect()
+    }
+
+    #[test]
+    fn new_channel_is_fetched_up_to_the_video_count() {
+        let source = fixture(&[("UCnew", 40)]);
+        let channels = Channels(vec![Channel::new("New", "UCnew", Vec::new())]);
+
+        let updates = refresh_all(source, &channels);
+
+        let videos = updates[0].result.as_ref().unwrap();
+        assert!(videos.len() >= 20);
+        assert_eq!(videos[0].id, "v00");
+    }
+
+    #[test]
+    fn refresh_stops_at_the_first_known_video() {
+        let source = fixture(&[("UCknown", 40)]);
+        let known = (15..40)
+            .map(|index| video(&format!("v{:02}", index)))
+            .collect();
+        let mut channels = Channels(vec![Channel::new("Known", "UCknown", known)]);
+
+        let updates = refresh_all(source, &channels);
+        let added = merge(&mut channels, updates.into_iter().next().unwrap());
+
+        // Reaching v15 takes a second page
+        assert_eq!(added.unwrap(), 15);
+        let channel = &channels[0];
+        assert_eq!(channel.videos.len(), 40);
+        assert_eq!(channel.videos[0].id, "v00");
+        assert_eq!(channel.unseen_count(), 15);
+        assert!(channel.refresh.last_error.is_none());
+    }
+
+    #[test]
+    fn filtered_shorts_do_not_end_or_shift_pages() {
+        let root = temp_dir("fixture");
+        fs::create_dir_all(root.join("channels")).unwrap();
+        let mut feed = flat_playlist(30);
+        for short in ["v02", "v05", "v12"] {
+            feed = feed.replace(&format!("watch?v={}", short), &format!("shorts/{}", short));
+        }
+        fs::write(root.join("channels").join("UCshorts.jsonl"), feed).unwrap();
+        let source: Arc<dyn VideoSource> = Arc::new(Fixture::new(root.to_str().unwrap()));
+
+        let known = (20..30)
+            .map(|index| video(&format!("v{:02}", index)))
+            .collect();
+        let mut channels = Channels(vec![Channel::new("Shorts", "UCshorts", known)]);
+
+        let update = refresh_all(source, &channels).into_iter().next().unwrap();
+        assert_eq!(merge(&mut channels, update).unwrap(), 17);
+        let ids = channels[0]
+            .videos
+            .iter()
+            .map(|video| video.id.as_str())
+            .collect::<Vec<_>>();
+        assert!(ids.contains(&"v19"));
+        assert!(!ids.contains(&"v12"));
+    }
+
+    #[test]
+    fn refreshed_videos_get_progress_played_before_subscribing() {
+        let source = fixture(&[("UCplayed", 20)]);
+        let known = (5..20)
+            .map(|index| video(&format!("v{:02}", index)))
+            .collect();
+        let mut channels = Channels(vec![Channel::new("Played", "UCplayed", known)]);
+        let mut progress = ProgressStore::default();
+        progress.set(
+            "v02",
+            WatchProgress {
+                current: 60,
+                duration: 600,
+                played: None,
+            },
+        );
+
+        let update = refresh_all(source, &channels).into_iter().next().unwrap();
+        merge_update(&mut channels, update, &progress, &Filters::default()).unwrap();
+
+        let played = channels[0]
+            .videos
+            .iter()
+            .find(|video| video.id == "v02")
+            .unwrap();
+        assert_eq!(played.progress.map(|progress| progress.current), Some(60));
+    }
+
+    #[test]
+    fn failed_channel_keeps_its_videos_and_records_the_error() {
+        let source = fixture(&[("UCgood", 5)]);
+        let mut channels = Channels(vec![
+            Channel::new("Good", "UCgood", vec![video("v03"), video("v04")]),
+            Channel::new("Missing", "UCmissing", vec![video("old")]),
+        ]);
+
+        for update in refresh_all(source, &channels) {
+            let is_good = update.channel.id == "UCgood";
+            assert_eq!(merge(&mut channels, update).is_ok(), is_good);
+        }
+
+        let missing = channels.iter().find(|c| c.id == "UCmissing").unwrap();
+        assert_eq!(missing.videos.len(), 1);
+        assert!(missing.refresh.last_error.is_some());
+
+        let good = channels.iter().find(|c| c.id == "UCgood").unwrap();
+        assert_eq!(good.videos.len(), 5);
+        assert!(good.refresh.last_error.is_none());
+    }
+
+    #[test]
+    fn updates_for_unsubscribed_channels_are_ignored() {
+        let source = fixture(&[("UCgone", 5)]);
+        let targets = vec![RefreshTarget::from(ChannelInfo {
+            id: "UCgone".to_owned(),
+            name: "Gone".to_owned(),
+            kind: SubscriptionKind::Channel,
+        })];
+        let mut refresh = fetch_updates(source, targets, &[ChannelTab::Videos], 5, 1);
+        let mut channels = Channels(Vec::new());
+
+        let update = refresh.next_update(true).unwrap();
+        let channel = update.channel.clone();
+        let result = merge_update(
+            &mut channels,
+            update,
+            &ProgressStore::default(),
+            &Filters::default(),
+        );
+        assert!(matches!(result, Ok(None)));
+        assert!(refresh.next_update(true).is_none());
+
+        // Neither updated nor failed in the refresh report
+        let mut report = RefreshReport::default();
+        report.add(channel, result);
+        assert!(report.updated.is_empty());
+        assert!(report.failed.is_empty());
+    }
+
+    #[test]
+    fn cancelling_kills_running_fetches() {
+        let source = Arc::new(fake_ytdlp_script("exec sleep 30"));
+        let targets = vec![RefreshTarget::from(ChannelInfo {
+            id: "UCslow".to_owned(),
+            name: "Slow".to_owned(),
+            kind: SubscriptionKind::Channel,
+        })];
+        let mut refresh = fetch_updates(source, targets, &[ChannelTab::Videos], 5, 1);
+
+        thread::sleep(Duration::from_millis(300));
+        refresh.cancel();
+
+        assert!(refresh.is_done());
+        let update = refresh.rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update.result, Err(Error::CommandFailed(_))));
+    }
+
+    #[test]
+    fn tabs_are_compared_against_their_own_videos() {
+        let root = temp_dir("fixture");
+        fs::create_dir_all(root.join("channels")).unwrap();
+        fs::write(
+            root.join("channels").join("UCtabs.jsonl"),
+            flat_playlist(10),
+        )
+        .unwrap();
+        // A premiere is listed under both tabs, ahead of older streams that were never fetched
+        let streams = (0..15)
+            .map(|index| {
+                let id = if index == 2 {
+                    "v05".to_owned()
+                } else {
+                    format!("s{:02}", index)
+                };
+                flat_entry(&id, "Stream", 1_700_000_000 - index * 3600)
+            })
+            .collect::<Vec<String>>();
+        fs::write(
+            root.join("channels").join("UCtabs.streams.jsonl"),
+            streams.join("\n"),
+        )
+        .unwrap();
+        let source: Arc<dyn VideoSource> = Arc::new(Fixture::new(root.to_str().unwrap()));
+
+        let known = (0..10)
+            .map(|index| video(&format!("v{:02}", index)))
+            .collect();
+        let mut channels = Channels(vec![Channel::new("Tabs", "UCtabs", known)]);
+        let targets = channels.iter().map(RefreshTarget::from).collect();
+        let mut refresh = fetch_updates(
+            source,
+            targets,
+            &[ChannelTab::Videos, ChannelTab::Streams],
+            20,
+            1,
+        );
+
+        let update = refresh.next_update(true).unwrap();
+        assert_eq!(merge(&mut channels, update).unwrap(), 14);
+        assert!(channels[0].videos.iter().any(|video| video.id == "s14"));
+    }
+
+    /// A channel feed of 30 videos from `flat_playlist`, each with a length and view count
+    fn detailed_feed(root: &std::path::Path, views: u64) {
+        let feed = flat_playlist(30)
+            .lines()
+            .map(|line| {
+                let mut entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                entry["duration"] = 60.into();
+                entry["view_count"] = views.into();
+                entry.to_string()
+            })
+            .collect::<Vec<String>>();
+        fs::write(
+            root.join("channels").join("UCdetails.jsonl"),
+            feed.join("\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn refreshes_update_and_backfill_details_of_known_videos() {
+        let root = temp_dir("fixture");
+        fs::create_dir_all(root.join("channels")).unwrap();
+        detailed_feed(&root, 500);
+        let source: Arc<dyn VideoSource> = Arc::new(Fixture::new(root.to_str().unwrap()));
+
+        // Cached before lengths and view counts were tracked
+        let known = (5..30)
+            .map(|index| video(&format!("v{:02}", index)))
+            .collect();
+        let mut channels = Channels(vec![Channel::new("Details", "UCdetails", known)]);
+
+        let update = refresh_all(Arc::clone(&source), &channels)
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(merge(&mut channels, update).unwrap(), 5);
+        let views = |channels: &Channels, id: &str| {
+            channels[0]
+                .videos
+                .iter()
+                .find(|video| video.id == id)
+                .unwrap()
+                .view_count
+        };
+        // Paging goes on past known videos while it fills in their details, up to the video count
+        assert_eq!(views(&channels, "v15"), Some(500));
+        assert_eq!(channels[0].videos[15].duration, Some(60));
+        assert_eq!(views(&channels, "v25"), None);
+
+        detailed_feed(&root, 900);
+        let update = refresh_all(source, &channels).into_iter().next().unwrap();
+        assert_eq!(merge(&mut channels, update).unwrap(), 0);
+        assert_eq!(views(&channels, "v03"), Some(900));
+    }
+
+    /// A playlist subscription, oldest item first like a course series, with its first `known` items known
+    fn course(items: usize, known: usize) -> (Arc<dyn VideoSource>, Channels) {
+        let root = temp_dir("fixture");
+        fs::create_dir_all(root.join("playlists")).unwrap();
+        let entries = (0..items)
+            .map(|index| {
+                flat_entry(
+                    &format!("p{:03}", index),
+                    "Lesson",
+                    1_700_000_000 + index as i64 * 3600,
+                )
+            })
+            .collect::<Vec<String>>();
+        fs::write(
+            root.join("playlists").join("PLcourse.jsonl"),
+            entries.join("\n"),
+        )
+        .unwrap();
+
+        let known = (0..known)
+            .map(|index| video(&format!("p{:03}", index)))
+            .collect();
+        let info = ChannelInfo {
+            id: "PLcourse".to_owned(),
+            name: "Course".to_owned(),
+            kind: SubscriptionKind::Playlist,
+        };
+        let source: Arc<dyn VideoSource> = Arc::new(Fixture::new(root.to_str().unwrap()));
+        (source, Channels(vec![Channel::from_info(&info, known)]))
+    }
+
+    #[test]
+    fn playlists_gain_items_added_to_their_end() {
+        let (source, mut channels) = course(8, 5);
+
+        let update = refresh_all(source, &channels).into_iter().next().unwrap();
+        assert_eq!(merge(&mut channels, update).unwrap(), 3);
+        assert_eq!(channels[0].videos[0].id, "p007");
+        assert_eq!(channels[0].unseen_count(), 3);
+    }
+
+    #[test]
+    fn long_playlists_are_paged_from_their_known_end() {
+        let (source, mut channels) = course(200, 30);
+
+        let update = refresh_all(source, &channels).into_iter().next().unwrap();
+        // Only as many as a refresh asks for, straight after the known items
+        assert_eq!(merge(&mut channels, update).unwrap(), 20);
+        let ids = channels[0]
+            .videos
+            .iter()
+            .map(|video| video.id.as_str())
+            .collect::<Vec<_>>();
+        assert!(ids.contains(&"p030"));
+        assert!(ids.contains(&"p049"));
+        assert!(!ids.contains(&"p050"));
+    }
 }
@@ -1,5 +1,10 @@
+use std::{collections::HashSet, path::PathBuf};
+
 use chrono::{DateTime, Days, Local};
 use crossterm::style::{Attribute, Color, StyledContent, Stylize};
+use itertools::Itertools;
+
+use crate::finder::fuzzy_match;
 
 fn apply_style(
     text: &str,
@@ -24,17 +29,35 @@ fn apply_style(
     styled_text
 }
 
+/// Highlights every character matched by each word of the query, the same way the finder matched them
 pub fn highlight_query(str: &str, query: Option<&str>, color: Option<Color>) -> String {
-    if let Some(query) = query {
-        if let Some(pos) = str.to_lowercase().find(&query.to_lowercase()) {
-            let before = apply_style(&str[..pos], color, false, false);
-            let matched = apply_style(&str[pos..pos + query.len()], Some(Color::Red), true, true);
-            let after = apply_style(&str[pos + query.len()..], color, false, false);
+    highlighted_chunks(str, query)
+        .into_iter()
+        .map(|(is_match, text)| {
+            if is_match {
+                apply_style(&text, Some(Color::Red), true, true).to_string()
+            } else {
+                apply_style(&text, color, false, false).to_string()
+            }
+        })
+        .collect()
+}
 
-            return format!("{}{}{}", before, matched, after);
-        }
-    }
-    apply_style(str, color, false, false).to_string()
+/// Splits text into runs of characters that were or were not matched by any word of the query
+fn highlighted_chunks(str: &str, query: Option<&str>) -> Vec<(bool, String)> {
+    let matched: HashSet<usize> = query
+        .into_iter()
+        .flat_map(|query| query.split_whitespace())
+        .filter_map(|term| fuzzy_match(str, term))
+        .flat_map(|found| found.indices)
+        .collect();
+
+    str.chars()
+        .enumerate()
+        .chunk_by(|(index, _)| matched.contains(index))
+        .into_iter()
+        .map(|(is_match, chars)| (is_match, chars.map(|(_, char)| char).collect()))
+        .collect()
 }
 
 pub fn seconds_formatted(time_second: u32) -> String {
@@ -95,3 +118,153 @@ pub fn time_since_formatted(date: DateTime<Local>) -> String {
         "Today".to_string()
     }
 }
+
+pub fn expand_path(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    PathBuf::from(path)
+}
+
+/// Relative time down to the minute, for recent events such as channel refreshes
+pub fn elapsed_formatted(date: DateTime<Local>) -> String {
+    let duration = Local::now().signed_duration_since(date);
+
+    let (amount, unit) = if duration.num_days() > 0 {
+        (duration.num_days(), "Day")
+    } else if duration.num_hours() > 0 {
+        (duration.num_hours(), "Hour")
+    } else if duration.num_minutes() > 0 {
+        (duration.num_minutes(), "Minute")
+    } else {
+        return "Just Now".to_string();
+    };
+
+    if amount > 1 {
+        format!("{} {}s Ago", amount, unit)
+    } else {
+        format!("1 {} Ago", unit)
+    }
+}
+
+/// Shortened view count, such as `1.2K Views`
+pub fn views_formatted(views: u64) -> String {
+    match views {
+        1 => return "1 View".to_string(),
+        0..1_000 => return format!("{} Views", views),
+        _ => (),
+    }
+
+    // Rounded before picking a unit, so 999,950 views are 1.0M rather than 1000K
+    let (amount, suffix) = [(1_000.0, "K"), (1_000_000.0, "M"), (1_000_000_000.0, "B")]
+        .into_iter()
+        .map(|(unit, suffix)| (views as f64 / unit, suffix))
+        .find(|(amount, suffix)| amount.round() < 1_000.0 || *suffix == "B")
+        .unwrap();
+
+    if amount < 9.95 {
+        format!("{:.1}{} Views", amount, suffix)
+    } else {
+        format!("{:.0}{} Views", amount, suffix)
+    }
+}
+
+/// A video's length and view count joined for feed entries, for whichever are known
+pub fn details_formatted(duration: Option<u32>, view_count: Option<u64>) -> Option<String> {
+    let details = [
+        duration.map(seconds_formatted),
+        view_count.map(views_formatted),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>();
+
+    if details.is_empty() {
+        None
+    } else {
+        Some(details.join(" • "))
+    }
+}
+
+/// The line under a video's title in lists, joining whichever of its channel, age, details and watch status are known
+pub fn entry_line_formatted(
+    channel: Option<&str>,
+    upload: DateTime<Local>,
+    details: Option<String>,
+    watch_status: Option<String>,
+) -> String {
+    [
+        channel.map(str::to_owned),
+        Some(time_since_formatted(upload)),
+        details,
+        watch_status,
+    ]
+    .into_iter()
+    .flatten()
+    .join(" • ")
+}
+
+/// Time left until a scheduled start, down to the minute
+pub fn time_until_formatted(date: DateTime<Local>) -> String {
+    let duration = date.signed_duration_since(Local::now());
+
+    if duration.num_days() > 0 {
+        format!(
+            "Starts in {}d {}h",
+            duration.num_days(),
+            duration.num_hours() % 24
+        )
+    } else if duration.num_hours() > 0 {
+        format!(
+            "Starts in {}h {}m",
+            duration.num_hours(),
+            duration.num_minutes() % 60
+        )
+    } else if duration.num_minutes() > 0 {
+        format!("Starts in {}m", duration.num_minutes())
+    } else {
+        "Starting Soon".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The text with each highlighted run in brackets
+    fn marked(str: &str, query: &str) -> String {
+        highlighted_chunks(str, Some(query))
+            .into_iter()
+            .map(|(is_match, text)| {
+                if is_match {
+                    format!("[{}]", text)
+                } else {
+                    text
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn highlights_every_matched_character() {
+        assert_eq!(marked("Linus Tech Tips", "ltt"), "[L]inus [T]ech [T]ips");
+        assert_eq!(
+            marked("Building a Rust Parser", "rust build"),
+            "[Build]ing a [Rust] Parser"
+        );
+        assert_eq!(marked("Cooking", "xyz"), "Cooking");
+    }
+
+    #[test]
+    fn views_are_rounded_before_picking_a_unit() {
+        assert_eq!(views_formatted(1), "1 View");
+        assert_eq!(views_formatted(999), "999 Views");
+        assert_eq!(views_formatted(1_234), "1.2K Views");
+        assert_eq!(views_formatted(9_960), "10K Views");
+        assert_eq!(views_formatted(999_499), "999K Views");
+        assert_eq!(views_formatted(999_950), "1.0M Views");
+        assert_eq!(views_formatted(2_500_000_000), "2.5B Views");
+    }
+}
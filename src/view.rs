@@ -1,86 +1,128 @@
 use std::{fmt::Display, rc::Rc};
 
 use crate::{
+    groups::Groups,
     mpv::WatchProgress,
-    yt::{Channel, ChannelIndex, VideoIndex, VideoInfo, VideoWatchLater},
+    sorting::SortMode,
+    updates::RefreshReport,
+    yt::{
+        Channel, ChannelId, ChannelInfo, ChannelTab, Channels, Video, VideoId, VideoInfo,
+        VideoWatchLater,
+    },
 };
 
 pub type LastView = Rc<ViewPage>;
 pub type LastIndex = usize;
 pub type VideoCount = usize;
-pub type WatchLaterIndex = usize;
+pub type GroupName = String;
 pub type LastSearch = Rc<(Vec<VideoInfo>, String)>;
 
 #[derive(Clone)]
 pub enum PlayType {
-    Existing(VideoIndex),
+    Existing(VideoId),
     New(VideoInfo, Option<LastSearch>),
-    WatchLater(WatchLaterIndex),
+    WatchLater(VideoId),
 }
 
 #[derive(Clone)]
 pub enum ViewPage {
-    Home,
-    ChannelFeed(ChannelIndex, Option<LastIndex>),
+    Home(Option<LastIndex>),
+    ChannelFeed(ChannelId, ChannelTab, Option<LastIndex>),
     MixedFeed(Option<LastIndex>),
+    NewFeed(Option<LastIndex>),
+    GroupFeed(GroupName, Option<LastIndex>),
     SearchChannels,
     Play(PlayType, LastView),
     Refreshing(LastView),
-    Information(VideoIndex, LastView),
+    RefreshReport(Rc<RefreshReport>, LastView),
+    Information(VideoId, LastView),
     SearchVideos,
     WatchLater,
+    ContinueWatching,
+    History,
+    Import,
 }
 
 #[derive(Clone)]
 pub enum Message {
     MixedFeed(Option<LastIndex>),
-    ChannelFeed(ChannelIndex, Option<LastIndex>),
+    NewFeed(Option<LastIndex>),
+    GroupFeed(GroupName, Option<LastIndex>),
+    ToggleGroup(GroupName, LastIndex),
+    SetGroup(ChannelId, Option<GroupName>, ViewPage),
+    MarkAllSeen,
+    MarkWatched(String, bool, ViewPage),
+    ToggleHideWatched(ViewPage),
+    ToggleShowHidden(ViewPage),
+    /// Shows or hides muted videos, then returns to the same video search results
+    ToggleShowHiddenSearch(LastSearch),
+    SetSortMode(SortMode, ViewPage),
+    ChannelFeed(ChannelId, ChannelTab, Option<LastIndex>),
     Play(PlayType),
-    Played(LastView, Option<VideoIndex>, Option<WatchProgress>),
+    Played(LastView, PlayType, Option<WatchProgress>),
     Subscribe(Channel),
-    Unsubscribe(ChannelIndex),
-    Information(VideoIndex, LastView),
-    MoreInformation(VideoIndex, LastView, String),
-    MoreVideos(ChannelIndex, ViewPage, VideoCount, LastIndex),
+    SubscribeAll(Vec<Channel>),
+    Unsubscribe(ChannelId),
+    Information(VideoId, LastView),
+    MoreInformation(VideoId, LastView, String),
+    MoreVideos(ChannelId, ViewPage, VideoCount, LastIndex),
     Refresh(ViewPage),
+    RefreshChannels(Vec<ChannelInfo>, ViewPage),
     WatchLater,
-    WatchLaterRemove(WatchLaterIndex),
+    WatchLaterRemove(VideoId),
     WatchLaterAdd(VideoWatchLater, LastView),
+    ContinueWatching,
+    History,
     SearchChannels,
     SearchVideosClean,
     SearchVideos,
+    Import,
     Quit,
     Home,
 }
 
+/// Which videos views leave out, toggled from feeds
+#[derive(Clone, Copy, Default)]
+pub struct Visibility {
+    pub hide_watched: bool,
+    /// Temporarily shows videos muted by the filter rules
+    pub show_hidden: bool,
+}
+
 #[derive(Debug)]
 pub enum Error {
     FileBadAccess,
     CommandFailed(String),
+    RequestFailed(String),
     JsonParsing,
     ChannelParsing,
     VideoParsing,
     VideoNotAvailable,
     TomlParsing,
-    InternalError(String),
+    XmlParsing,
+    Internal(String),
 }
 
 impl From<ViewPage> for Message {
     fn from(view: ViewPage) -> Self {
         match view {
-            ViewPage::Home => Message::Home,
-            ViewPage::ChannelFeed(channel_index, last_index) => {
-                Message::ChannelFeed(channel_index, last_index)
+            ViewPage::Home(_) => Message::Home,
+            ViewPage::ChannelFeed(channel_id, tab, last_index) => {
+                Message::ChannelFeed(channel_id, tab, last_index)
             }
             ViewPage::MixedFeed(last_index) => Message::MixedFeed(last_index),
+            ViewPage::NewFeed(last_index) => Message::NewFeed(last_index),
+            ViewPage::GroupFeed(name, last_index) => Message::GroupFeed(name, last_index),
             ViewPage::SearchChannels => Message::SearchChannels,
             ViewPage::SearchVideos => Message::SearchVideos,
-            ViewPage::Play(video_index, _) => Message::Play(video_index),
+            ViewPage::Play(play_type, _) => Message::Play(play_type),
             ViewPage::Refreshing(view_page) => Message::Refresh(view_page.as_ref().clone()),
+            ViewPage::RefreshReport(_, view_page) => view_page.as_ref().clone().into(),
             ViewPage::WatchLater => Message::WatchLater,
-            ViewPage::Information(video_index, view_page) => {
-                Message::Information(video_index, view_page)
-            }
+            ViewPage::ContinueWatching => Message::ContinueWatching,
+            ViewPage::History => Message::History,
+            ViewPage::Import => Message::Import,
+            ViewPage::Information(video_id, view_page) => Message::Information(video_id, view_page),
         }
     }
 }
@@ -90,18 +132,26 @@ impl Display for Error {
         let msg = match self {
             Error::FileBadAccess => "Could not access file".to_owned(),
             Error::CommandFailed(command) => format!("Could not run command: {}", command),
+            Error::RequestFailed(request) => format!("Could not complete request: {}", request),
             Error::JsonParsing => "Could not parse JSON".to_owned(),
             Error::ChannelParsing => "Could not parse channel information from yt-dlp".to_owned(),
             Error::VideoParsing => "Could not parse video information from yt-dlp".to_owned(),
             Error::TomlParsing => "Could not load toml configuration".to_owned(),
+            Error::XmlParsing => "Could not parse XML".to_owned(),
             Error::VideoNotAvailable => "Fetched video was not available".to_owned(),
-            Error::InternalError(e) => format!("Internal Error({})", e.to_string()),
+            Error::Internal(e) => format!("Internal Error({})", e),
         };
 
         write!(f, "{}", msg)
     }
 }
 
+impl Visibility {
+    pub fn shows(&self, video: &Video) -> bool {
+        (!self.hide_watched || !video.watched) && (self.show_hidden || !video.hidden)
+    }
+}
+
 impl ViewPage {
     pub fn or_inner(&self) -> &ViewPage {
         match self {
@@ -111,4 +161,33 @@ impl ViewPage {
             _ => self,
         }
     }
+
+    /// Whether the channel, group or video this view shows still exists
+    pub fn exists(
+        &self,
+        channels: &Channels,
+        groups: &Groups,
+        watch_later: &[VideoWatchLater],
+    ) -> bool {
+        match self {
+            ViewPage::ChannelFeed(channel_id, _, _) => channels.channel(channel_id).is_some(),
+            ViewPage::GroupFeed(name, _) => groups.group(name).is_some(),
+            ViewPage::Information(video_id, _)
+            | ViewPage::Play(PlayType::Existing(video_id), _) => channels.video(video_id).is_some(),
+            ViewPage::Play(PlayType::WatchLater(video_id), _) => {
+                watch_later.iter().any(|later| later.is(video_id))
+            }
+            _ => true,
+        }
+    }
+
+    /// Where to go instead when what this view shows no longer exists
+    pub fn fallback(&self) -> ViewPage {
+        match self {
+            ViewPage::Play(_, view_page) | ViewPage::Information(_, view_page) => {
+                view_page.as_ref().clone()
+            }
+            _ => ViewPage::Home(None),
+        }
+    }
 }
@@ -0,0 +1,194 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use chrono::{DateTime, Local};
+use crossterm::style::{Color, Stylize};
+use itertools::Itertools;
+
+use crate::{
+    clear_screen,
+    config::Config,
+    finder::{Findable, Finder},
+    page::Page,
+    utilities::{entry_line_formatted, highlight_query},
+    view::{Message, PlayType, ViewPage, Visibility},
+    yt::{Channels, Video, VideoId, VideoWatchLater},
+};
+
+use super::{View, ViewInput};
+
+#[derive(Clone)]
+enum ContinueEntry<'a> {
+    Channel(VideoId, &'a str, &'a Video),
+    WatchLater(&'a VideoWatchLater),
+}
+
+impl<'a> ContinueEntry<'a> {
+    fn get_video(&self) -> &'a Video {
+        match self {
+            ContinueEntry::Channel(_, _, video) => video,
+            ContinueEntry::WatchLater(later) => &later.video,
+        }
+    }
+
+    fn get_channel(&self) -> &'a str {
+        match self {
+            ContinueEntry::Channel(_, channel, _) => channel,
+            ContinueEntry::WatchLater(later) => &later.channel.name,
+        }
+    }
+}
+
+impl Findable for ContinueEntry<'_> {
+    fn title(&self) -> &str {
+        &self.get_video().title
+    }
+
+    fn channel(&self) -> Option<&str> {
+        Some(self.get_channel())
+    }
+
+    fn date(&self) -> DateTime<Local> {
+        self.get_video().upload
+    }
+
+    fn video(&self) -> Option<&Video> {
+        Some(self.get_video())
+    }
+}
+
+/// Started, but not far enough in to be finished
+fn is_partially_watched(video: &Video, config: &Config) -> bool {
+    !video.watched
+        && video.progress.is_some_and(|progress| {
+            progress.current >= config.continue_after_seconds
+                && (progress.current as u64) * 100
+                    < progress.duration as u64 * config.continue_until_percent as u64
+        })
+}
+
+pub fn show(
+    channels: &Channels,
+    watch_later: &[VideoWatchLater],
+    config: &Config,
+    visibility: Visibility,
+) -> Message {
+    let shown = |video: &Video| {
+        is_partially_watched(video, config) && (visibility.show_hidden || !video.hidden)
+    };
+
+    let from_channels = channels
+        .videos_mixed()
+        .into_iter()
+        .filter(|(_, _, video)| shown(video))
+        .map(|(index, channel, video)| ContinueEntry::Channel(index, &channel.name, video))
+        .collect_vec();
+
+    // Watch later keeps its own copy of a video, so prefer the subscription's when both exist
+    let ids: HashSet<&str> = from_channels
+        .iter()
+        .map(|entry| entry.get_video().id.as_str())
+        .collect();
+
+    let videos = watch_later
+        .iter()
+        .filter(|later| shown(&later.video) && !ids.contains(later.video.id.as_str()))
+        .map(ContinueEntry::WatchLater)
+        .chain(from_channels)
+        .sorted_by_key(|entry| Reverse(entry.get_video().progress.and_then(|p| p.played)))
+        .collect_vec();
+
+    let mut view = View::new(
+        "Continue Watching".to_owned(),
+        "(p)revious, (n)ext, (f)ind, (H)idden, (b)ack, (q)uit".to_owned(),
+        "▶".to_owned(),
+    );
+
+    let mut page_normal = Page::new(videos.len(), 3);
+    let mut finder = Finder::new(videos.len(), 3);
+
+    clear_screen();
+
+    loop {
+        view.clear_content();
+        view.update_page(Some(finder.page_or(&page_normal)));
+
+        finder
+            .page_or(&page_normal)
+            .current_page(finder.videos_or(&videos))
+            .iter()
+            .enumerate()
+            .for_each(|(i, entry)| {
+                let video = entry.get_video();
+                let details = entry_line_formatted(
+                    Some(entry.get_channel()),
+                    video.upload,
+                    video.details(),
+                    Some(video.watch_status()),
+                );
+
+                view.add_line(format!(
+                    "{}. {}\n   {}\n",
+                    i.to_string().green(),
+                    highlight_query(&video.title, finder.terms(), Some(Color::Yellow)),
+                    details
+                ));
+            });
+
+        let page = finder.page_or_mut(&mut page_normal);
+
+        match view.show() {
+            ViewInput::Esc => {
+                let should_reset = finder
+                    .query()
+                    .map(|query| !query.is_empty())
+                    .unwrap_or(false);
+
+                if should_reset {
+                    finder.reset(&mut view)
+                }
+            }
+            ViewInput::Char(char) => match char {
+                'q' => return Message::Quit,
+                'b' => return Message::Home,
+                'H' => return Message::ToggleShowHidden(ViewPage::ContinueWatching),
+                'n' => {
+                    page.next_page();
+                    view.clear_error();
+                }
+                'p' => {
+                    page.prev_page();
+                    view.clear_error();
+                }
+                'f' => {
+                    view.clear_error();
+                    let Some(input) = view.show_with_input() else {
+                        finder.reset(&mut view);
+                        continue;
+                    };
+
+                    if let Err(err) = finder.search(&mut view, &videos, &input) {
+                        view.set_error(&err);
+                    }
+                }
+                input => {
+                    view.set_error(&format!("{} is not a valid option!", input));
+                }
+            },
+            ViewInput::Num(num) => {
+                let item = finder
+                    .page_or(&page_normal)
+                    .item_at_index(finder.videos_or(&videos), num);
+
+                match item {
+                    Some(ContinueEntry::Channel(video_id, _, _)) => {
+                        return Message::Play(PlayType::Existing(video_id.clone()));
+                    }
+                    Some(ContinueEntry::WatchLater(later)) => {
+                        return Message::Play(PlayType::WatchLater(later.video_id()));
+                    }
+                    None => view.set_error(&format!("{} is not a valid option!", num)),
+                }
+            }
+        }
+    }
+}
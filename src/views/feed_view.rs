@@ -1,115 +1,295 @@
-use std::cmp::Ordering;
+use std::cmp::min;
 
-use crossterm::style::{Color, Stylize};
+use chrono::{DateTime, Local};
+use crossterm::{
+    style::{Color, Stylize},
+    terminal,
+};
 use itertools::Itertools;
 
 use crate::{
     clear_screen,
-    finder::Finder,
+    finder::{Findable, Finder},
+    groups::Groups,
     page::Page,
-    utilities::{self, time_since_formatted},
-    view::{Message, PlayType, ViewPage},
-    yt::{ChannelIndex, Channels, Video, VideoIndex},
+    sorting::{SortMode, SortedFeed},
+    utilities::{self, entry_line_formatted, time_until_formatted},
+    view::{Message, PlayType, ViewPage, Visibility},
+    yt::{Channel, ChannelId, ChannelTab, Channels, SubscriptionKind, Video, VideoId},
 };
 
 use super::{View, ViewInput};
 
+/// Which feed is being shown
+#[derive(Clone, Copy)]
+enum Feed<'a> {
+    /// A channel's tab, along with every tab that can be switched to
+    Channel(&'a ChannelId, ChannelTab, &'a [ChannelTab]),
+    /// Every channel's videos, along with their upcoming premieres and livestreams
+    Mixed(&'a [(&'a Channel, &'a Video)]),
+    New,
+    Group(&'a str),
+}
+
+/// Upcoming videos listed above the subscription feed, the rest are summarized
+const UPCOMING_SHOWN: usize = 3;
+
 #[derive(Clone)]
 enum VideoEntry<'a> {
-    Mixed(usize, usize, &'a String, &'a Video),
-    Channel(usize, &'a Video),
+    Mixed(VideoId, &'a String, &'a Video),
+    /// Along with its channel's name, which is not shown but can still be searched
+    Channel(VideoId, &'a String, &'a Video),
 }
 
 impl<'a> VideoEntry<'a> {
     fn get_video(&'a self) -> &'a Video {
         match self {
-            VideoEntry::Mixed(_, _, _, video) => video,
-            VideoEntry::Channel(_, video) => video,
+            VideoEntry::Mixed(_, _, video) => video,
+            VideoEntry::Channel(_, _, video) => video,
         }
     }
 
     fn get_channel(&'a self) -> Option<&'a str> {
         match self {
-            VideoEntry::Mixed(_, _, channel, _) => Some(channel),
-            VideoEntry::Channel(_, _) => None,
+            VideoEntry::Mixed(_, channel, _) => Some(channel),
+            VideoEntry::Channel(_, _, _) => None,
+        }
+    }
+
+    fn get_channel_name(&'a self) -> &'a str {
+        match self {
+            VideoEntry::Mixed(_, channel, _) => channel,
+            VideoEntry::Channel(_, channel, _) => channel,
+        }
+    }
+
+    fn get_id(&'a self) -> &'a VideoId {
+        match self {
+            VideoEntry::Mixed(id, _, _) => id,
+            VideoEntry::Channel(id, _, _) => id,
         }
     }
 
     fn get_title_formatted(&'a self, query: Option<&str>) -> String {
         let video = self.get_video();
-        if video.progress.is_some() {
+        let title = if video.watched {
+            utilities::highlight_query(&video.title, query, Some(Color::DarkGrey))
+        } else if video.progress.is_some() {
             utilities::highlight_query(&video.title, query, Some(Color::Yellow))
         } else {
             utilities::highlight_query(&video.title, query, Some(Color::DarkYellow))
+        };
+
+        if video.seen {
+            title
+        } else {
+            format!("{} {}", "●".cyan(), title)
         }
     }
+}
+
+impl Findable for VideoEntry<'_> {
+    fn title(&self) -> &str {
+        &self.get_video().title
+    }
+
+    fn channel(&self) -> Option<&str> {
+        Some(self.get_channel_name())
+    }
 
-    fn cmp(&'a self, other: &'a VideoEntry) -> Ordering {
-        other.get_video().upload.cmp(&self.get_video().upload)
+    fn date(&self) -> DateTime<Local> {
+        self.get_video().upload
+    }
+
+    fn video(&self) -> Option<&Video> {
+        Some(self.get_video())
     }
 }
 
+/// A single tab of a channel's feed. Playlists only have their videos
 pub fn show_channel(
-    channel_index: ChannelIndex,
+    channel_id: &ChannelId,
+    tab: ChannelTab,
+    tabs: &[ChannelTab],
     channels: &Channels,
     last_index: Option<usize>,
+    visibility: Visibility,
+    sort: SortMode,
 ) -> Message {
-    let videos = channels
-        .channel(channel_index)
-        .unwrap()
+    let channel = channels.channel(channel_id).unwrap();
+    let tabs = match channel.kind {
+        SubscriptionKind::Channel => tabs,
+        SubscriptionKind::Playlist => &[ChannelTab::Videos],
+    };
+    let videos = channel
         .videos
         .iter()
-        .enumerate()
-        .map(|(i, video)| VideoEntry::Channel(i, video))
+        .filter(|video| video.tab == tab && visibility.shows(video))
+        .map(|video| VideoEntry::Channel(channel.video_id(video), &channel.name, video))
+        .collect();
+
+    show_feed(
+        videos,
+        channels,
+        last_index,
+        Feed::Channel(channel_id, tab, tabs),
+        sort,
+    )
+}
+
+pub fn show_mixed(
+    channels: &Channels,
+    last_index: Option<usize>,
+    visibility: Visibility,
+    sort: SortMode,
+) -> Message {
+    let videos: Vec<VideoEntry> = channels
+        .videos_mixed()
+        .into_iter()
+        .filter(|(_, channel, video)| {
+            video.tab == ChannelTab::Videos
+                && visibility.shows(video)
+                && (visibility.show_hidden || !channel.excluded_from_mixed)
+        })
+        .map(|(id, channel, video)| VideoEntry::Mixed(id, &channel.name, video))
         .collect();
+    let upcoming = channels
+        .upcoming_mixed()
+        .into_iter()
+        .filter(|(channel, video)| {
+            visibility.show_hidden || (!video.hidden && !channel.excluded_from_mixed)
+        })
+        .collect_vec();
 
-    show_feed(&videos, channels, last_index, Some(channel_index))
+    show_feed(videos, channels, last_index, Feed::Mixed(&upcoming), sort)
 }
 
-pub fn show_mixed(channels: &Channels, last_index: Option<usize>) -> Message {
+/// Only videos that have not been seen yet, across every channel
+pub fn show_new(
+    channels: &Channels,
+    last_index: Option<usize>,
+    visibility: Visibility,
+    sort: SortMode,
+) -> Message {
     let videos: Vec<VideoEntry> = channels
-        .iter()
-        .enumerate()
-        .flat_map(|(i, channel)| -> Vec<VideoEntry> {
-            channel
-                .videos
-                .iter()
-                .enumerate()
-                .map(|(j, video)| VideoEntry::Mixed(i, j, &channel.name, video))
-                .collect()
+        .videos_mixed()
+        .into_iter()
+        .filter(|(_, _, video)| {
+            video.tab == ChannelTab::Videos && !video.seen && visibility.shows(video)
         })
-        .sorted_by(|a, b| a.cmp(b))
+        .map(|(id, channel, video)| VideoEntry::Mixed(id, &channel.name, video))
         .collect();
 
-    show_feed(&videos, channels, last_index, None)
+    show_feed(videos, channels, last_index, Feed::New, sort)
+}
+
+/// Every video from the channels in a group, newest first
+pub fn show_group(
+    name: &str,
+    groups: &Groups,
+    channels: &Channels,
+    last_index: Option<usize>,
+    visibility: Visibility,
+    sort: SortMode,
+) -> Message {
+    let channel_ids = groups
+        .group(name)
+        .map(|group| group.channel_ids.as_slice())
+        .unwrap_or_default();
+
+    let videos: Vec<VideoEntry> = channels
+        .videos_mixed()
+        .into_iter()
+        .filter(|(_, channel, video)| {
+            channel_ids.contains(&channel.id)
+                && video.tab == ChannelTab::Videos
+                && visibility.shows(video)
+        })
+        .map(|(id, channel, video)| VideoEntry::Mixed(id, &channel.name, video))
+        .collect();
+
+    show_feed(videos, channels, last_index, Feed::Group(name), sort)
 }
 
 fn show_feed(
-    videos: &Vec<VideoEntry>,
+    mut videos: Vec<VideoEntry>,
     channels: &Channels,
     last_index: Option<usize>,
-    channel_index: Option<ChannelIndex>,
+    feed: Feed,
+    sort: SortMode,
 ) -> Message {
+    videos.sort_by(|a, b| {
+        sort.compare(
+            (a.get_channel().unwrap_or_default(), a.get_video()),
+            (b.get_channel().unwrap_or_default(), b.get_video()),
+        )
+    });
+    let videos = &videos;
+
     let mut page_normal = Page::new(videos.len(), 3);
     let mut finder = Finder::new(videos.len(), 3);
 
-    page_normal.current_index = last_index.unwrap_or(page_normal.current_index);
-    let channel = channel_index.map(|index| channels.channel(index).unwrap());
+    let channel_id = match feed {
+        Feed::Channel(channel_id, _, _) => Some(channel_id),
+        _ => None,
+    };
 
-    let mut view = if let Some(channel) = channel {
-        View::new(
-            format!("{}'s Feed", &channel.name),
-            "(p)revious, (n)ext, (m)ore, (f)ind, (r)efresh, (u)nsubscribe, (b)ack, (q)uit"
+    let (title, options) = match feed {
+        Feed::Channel(channel_id, tab, tabs) => (
+            match tab {
+                ChannelTab::Videos => {
+                    format!("{}'s Feed", &channels.channel(channel_id).unwrap().name)
+                }
+                tab => format!(
+                    "{}'s {}",
+                    &channels.channel(channel_id).unwrap().name,
+                    tab.name()
+                ),
+            },
+            format!(
+                "(p)revious, (n)ext, (m)ore, (f)ind, (s)ort, (w)atched, (h)ide watched, (H)idden, {}(g)roup, (r)efresh, (u)nsubscribe, (b)ack, (q)uit",
+                if tabs.len() > 1 { "(t)ab, " } else { "" }
+            ),
+        ),
+        Feed::New => (
+            "New Videos".to_owned(),
+            "(p)revious, (n)ext, (f)ind, (s)ort, (w)atched, (h)ide watched, (H)idden, (c)lear new, (r)efresh, (b)ack, (q)uit".to_owned(),
+        ),
+        Feed::Group(name) => (
+            format!("{} Feed", name),
+            "(p)revious, (n)ext, (f)ind, (s)ort, (w)atched, (h)ide watched, (H)idden, (r)efresh, (b)ack, (q)uit"
                 .to_owned(),
-            "▶".to_owned(),
-        )
-    } else {
-        View::new(
+        ),
+        Feed::Mixed(_) => (
             "Subscription Feed".to_owned(),
-            "(p)revious, (n)ext, (f)ind, (r)efresh, (b)ack, (q)uit".to_owned(),
-            "▶".to_owned(),
-        )
+            "(p)revious, (n)ext, (f)ind, (s)ort, (w)atched, (h)ide watched, (H)idden, (r)efresh, (b)ack, (q)uit"
+                .to_owned(),
+        ),
+    };
+
+    let title = match sort {
+        SortMode::Newest => title,
+        sort => format!("{} • {}", title, sort.name()),
     };
+    let mut view = View::new(title, options, "▶".to_owned());
+
+    let upcoming = match feed {
+        Feed::Mixed(upcoming) => upcoming,
+        _ => &[],
+    };
+
+    if !upcoming.is_empty() {
+        // Leave room for the upcoming section so the page does not scroll
+        let lines = min(upcoming.len(), UPCOMING_SHOWN) + 3;
+        let fits = (terminal::size().unwrap().1 as usize / 3).saturating_sub(4 + lines.div_ceil(3));
+        page_normal.count_per_page = min(page_normal.count_per_page, fits.max(1));
+    }
+
+    // The feed may have shrunk since it was left, such as after hiding watched videos from its last page
+    page_normal.current_index = min(
+        last_index.unwrap_or(page_normal.current_index),
+        videos.len().saturating_sub(page_normal.count_per_page),
+    );
 
     clear_screen();
 
@@ -117,6 +297,32 @@ fn show_feed(
         view.clear_content();
         view.update_page(Some(finder.page_or(&page_normal)));
 
+        if !upcoming.is_empty() && finder.query().is_none() {
+            let mut section = format!("{}\n", "Upcoming".magenta().bold());
+
+            upcoming
+                .iter()
+                .take(UPCOMING_SHOWN)
+                .for_each(|(channel, video)| {
+                    section.push_str(&format!(
+                        "   {} {} • {} • {}\n",
+                        "◷".cyan(),
+                        video.title.clone().dark_cyan(),
+                        channel.name,
+                        time_until_formatted(video.upload).yellow()
+                    ));
+                });
+
+            if upcoming.len() > UPCOMING_SHOWN {
+                section.push_str(&format!(
+                    "   {}\n",
+                    format!("+{} more", upcoming.len() - UPCOMING_SHOWN).dark_grey()
+                ));
+            }
+
+            view.add_line(section);
+        }
+
         let iter = finder
             .page_or(&page_normal)
             .current_page(finder.videos_or(videos))
@@ -125,33 +331,19 @@ fn show_feed(
 
         iter.for_each(|(i, entry)| {
             let video = entry.get_video();
-            let line = if let Some(channel) = entry.get_channel() {
-                format!(
-                    "{}. {}\n   {} • {} • {}\n",
-                    i.to_string().green(),
-                    entry.get_title_formatted(finder.query()),
-                    channel,
-                    time_since_formatted(video.upload),
-                    video
-                        .progress
-                        .as_ref()
-                        .map(|progress| progress.formatted())
-                        .unwrap_or("Not Watched".to_owned())
-                )
-            } else {
-                format!(
-                    "{}. {}\n   {} • {}\n",
-                    i.to_string().green(),
-                    entry.get_title_formatted(finder.query()),
-                    time_since_formatted(video.upload),
-                    video
-                        .progress
-                        .as_ref()
-                        .map(|progress| progress.formatted())
-                        .unwrap_or("Not Watched".to_owned())
-                )
-            };
-            view.add_line(line);
+            let details = entry_line_formatted(
+                entry.get_channel(),
+                video.upload,
+                video.details(),
+                Some(video.watch_status()),
+            );
+
+            view.add_line(format!(
+                "{}. {}\n   {}\n",
+                i.to_string().green(),
+                entry.get_title_formatted(finder.terms()),
+                details
+            ));
         });
 
         let page = finder.page_or_mut(&mut page_normal);
@@ -171,20 +363,106 @@ fn show_feed(
                 'q' => return Message::Quit,
                 'b' => return Message::Home,
                 'r' => {
-                    if let Some(index) = channel_index {
-                        return Message::Refresh(ViewPage::ChannelFeed(
-                            index,
-                            Some(page_normal.current_index),
-                        ));
+                    return Message::Refresh(feed_page(feed, page_normal.current_index));
+                }
+                'h' => {
+                    return Message::ToggleHideWatched(feed_page(feed, page_normal.current_index));
+                }
+                'H' => {
+                    return Message::ToggleShowHidden(feed_page(feed, page_normal.current_index));
+                }
+                's' => {
+                    view.clear_error();
+                    let modes = SortedFeed::of(&feed_page(feed, 0))
+                        .map(|sorted| sorted.modes())
+                        .unwrap_or_default();
+                    view.set_input(&format!(
+                        "Sort by {}:",
+                        modes
+                            .iter()
+                            .enumerate()
+                            .map(|(i, mode)| format!("({}) {}", i, mode.name()))
+                            .join(", ")
+                    ));
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    let mode = input
+                        .and_then(|input| input.trim().parse::<usize>().ok())
+                        .and_then(|num| modes.get(num));
+
+                    match mode {
+                        Some(mode) => return Message::SetSortMode(*mode, feed_page(feed, 0)),
+                        None => view.set_error("Not a valid sort number!"),
+                    }
+                }
+                'w' => {
+                    view.clear_error();
+                    view.set_input("Toggle watched for video:");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    let item = input
+                        .and_then(|input| input.trim().parse::<usize>().ok())
+                        .and_then(|num| {
+                            finder
+                                .page_or(&page_normal)
+                                .item_at_index(finder.videos_or(videos), num)
+                        });
+
+                    match item {
+                        Some(entry) => {
+                            let video = entry.get_video();
+                            return Message::MarkWatched(
+                                video.id.clone(),
+                                !video.watched,
+                                feed_page(feed, page_normal.current_index),
+                            );
+                        }
+                        None => view.set_error("Not a valid video number!"),
+                    }
+                }
+                'c' => {
+                    if matches!(feed, Feed::New) {
+                        return Message::MarkAllSeen;
                     } else {
-                        return Message::Refresh(ViewPage::MixedFeed(Some(
-                            page_normal.current_index,
-                        )));
+                        view.set_error("c is not a valid option!");
+                    }
+                }
+                'g' => {
+                    let Some(channel_id) = channel_id else {
+                        view.set_error("g is not a valid option!");
+                        continue;
+                    };
+
+                    view.clear_error();
+                    view.set_input("Move to group (empty to ungroup):");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    if let Some(input) = input {
+                        let name = Some(input.trim().to_owned()).filter(|name| !name.is_empty());
+                        return Message::SetGroup(
+                            channel_id.clone(),
+                            name,
+                            feed_page(feed, page_normal.current_index),
+                        );
                     }
                 }
+                't' => match feed {
+                    Feed::Channel(channel_id, tab, tabs) if tabs.len() > 1 => {
+                        let next = tabs
+                            .iter()
+                            .position(|other| *other == tab)
+                            .map(|index| tabs[(index + 1) % tabs.len()])
+                            .unwrap_or_default();
+                        return Message::ChannelFeed(channel_id.clone(), next, None);
+                    }
+                    _ => view.set_error("t is not a valid option!"),
+                },
                 'u' => {
-                    if let Some(index) = channel_index {
-                        return Message::Unsubscribe(index);
+                    if let Some(channel_id) = channel_id {
+                        return Message::Unsubscribe(channel_id.clone());
                     } else {
                         view.set_error("u is not a valid option!");
                     }
@@ -198,11 +476,27 @@ fn show_feed(
                     view.clear_error();
                 }
                 'm' => {
-                    if let Some(channel_index) = channel_index {
+                    if let Feed::Channel(channel_id, tab, _) = feed {
+                        // Hidden videos are still stored, so count them to continue past the last one fetched
+                        let stored = channels
+                            .channel(channel_id)
+                            .map(|channel| {
+                                channel
+                                    .videos
+                                    .iter()
+                                    .filter(|video| video.tab == tab)
+                                    .count()
+                            })
+                            .unwrap_or_default();
+
                         return Message::MoreVideos(
-                            channel_index,
-                            ViewPage::ChannelFeed(channel_index, Some(page.current_index)),
-                            page.last_index(),
+                            channel_id.clone(),
+                            ViewPage::ChannelFeed(
+                                channel_id.clone(),
+                                tab,
+                                Some(page.current_index),
+                            ),
+                            stored,
                             page.current_index,
                         );
                     } else {
@@ -216,19 +510,9 @@ fn show_feed(
                         continue;
                     };
 
-                    let filtered = videos
-                        .iter()
-                        .filter(|video| {
-                            video
-                                .get_video()
-                                .title
-                                .to_lowercase()
-                                .contains(&input.to_lowercase())
-                        })
-                        .cloned()
-                        .collect_vec();
-
-                    finder.update(&mut view, filtered, &input.to_lowercase());
+                    if let Err(err) = finder.search(&mut view, videos, &input) {
+                        view.set_error(&err);
+                    }
                 }
                 input => {
                     view.set_error(&format!("{} is not a valid option!", input));
@@ -239,20 +523,25 @@ fn show_feed(
                     .page_or(&page_normal)
                     .item_at_index(finder.videos_or(videos), num);
 
-                if let Some(VideoEntry::Mixed(channel_index, video_index, _, _)) = item {
-                    return Message::Play(PlayType::Existing(VideoIndex {
-                        channel_index: *channel_index,
-                        video_index: *video_index,
-                    }));
-                } else if let Some(VideoEntry::Channel(video_index, _)) = item {
-                    return Message::Play(PlayType::Existing(VideoIndex {
-                        channel_index: *channel_index.unwrap(),
-                        video_index: *video_index,
-                    }));
-                } else {
-                    view.set_error(&format!("{} is not a valid option!", num));
+                match item {
+                    Some(entry) => {
+                        return Message::Play(PlayType::Existing(entry.get_id().clone()))
+                    }
+                    None => view.set_error(&format!("{} is not a valid option!", num)),
                 }
             }
         }
     }
 }
+
+/// The view page for the feed currently being shown, used to return to it at the same position
+fn feed_page(feed: Feed, current_index: usize) -> ViewPage {
+    match feed {
+        Feed::Channel(channel_id, tab, _) => {
+            ViewPage::ChannelFeed(channel_id.clone(), tab, Some(current_index))
+        }
+        Feed::New => ViewPage::NewFeed(Some(current_index)),
+        Feed::Group(name) => ViewPage::GroupFeed(name.to_owned(), Some(current_index)),
+        Feed::Mixed(_) => ViewPage::MixedFeed(Some(current_index)),
+    }
+}
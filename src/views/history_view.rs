@@ -0,0 +1,159 @@
+use crossterm::style::{Color, Stylize};
+
+use crate::{
+    clear_screen,
+    filters::Filters,
+    finder::Finder,
+    history::{self, HistoryEntry},
+    page::Page,
+    utilities::{elapsed_formatted, expand_path, highlight_query, seconds_formatted},
+    view::{Message, PlayType, ViewPage, Visibility},
+    yt::Channels,
+};
+
+use super::{View, ViewInput};
+
+pub fn show(channels: &Channels, filters: &Filters, visibility: Visibility) -> Message {
+    // History does not keep video lengths, so only titles can be muted
+    let mut entries = history::load()
+        .into_iter()
+        .filter(|entry| visibility.show_hidden || !filters.hides(&entry.title, None))
+        .collect::<Vec<HistoryEntry>>();
+
+    let mut view = View::new(
+        "Watch History".to_owned(),
+        "(p)revious, (n)ext, (f)ind, (c)lear, (e)xport, (H)idden, (b)ack, (q)uit".to_owned(),
+        "▶".to_owned(),
+    );
+
+    let mut page_normal = Page::new(entries.len(), 3);
+    let mut finder: Finder<HistoryEntry> = Finder::new(entries.len(), 3);
+
+    clear_screen();
+
+    loop {
+        view.clear_content();
+        view.update_page(Some(finder.page_or(&page_normal)));
+
+        finder
+            .page_or(&page_normal)
+            .current_page(finder.videos_or(&entries))
+            .iter()
+            .enumerate()
+            .for_each(|(i, entry)| {
+                view.add_line(format!(
+                    "{}. {}\n   {} • {} • {} → {}\n",
+                    i.to_string().green(),
+                    highlight_query(&entry.title, finder.terms(), Some(Color::Yellow)),
+                    highlight_query(&entry.channel_name, finder.terms(), None),
+                    elapsed_formatted(entry.watched_at),
+                    seconds_formatted(entry.start_seconds),
+                    seconds_formatted(entry.end_seconds)
+                ));
+            });
+
+        let page = finder.page_or_mut(&mut page_normal);
+
+        match view.show() {
+            ViewInput::Esc => {
+                let should_reset = finder
+                    .query()
+                    .map(|query| !query.is_empty())
+                    .unwrap_or(false);
+
+                if should_reset {
+                    finder.reset(&mut view)
+                }
+            }
+            ViewInput::Char(char) => match char {
+                'q' => return Message::Quit,
+                'b' => return Message::Home,
+                'H' => return Message::ToggleShowHidden(ViewPage::History),
+                'n' => {
+                    page.next_page();
+                    view.clear_error();
+                }
+                'p' => {
+                    page.prev_page();
+                    view.clear_error();
+                }
+                'f' => {
+                    view.clear_error();
+                    let Some(input) = view.show_with_input() else {
+                        finder.reset(&mut view);
+                        continue;
+                    };
+
+                    if let Err(err) = finder.search(&mut view, &entries, &input) {
+                        view.set_error(&err);
+                    }
+                }
+                'c' => {
+                    view.clear_error();
+                    view.set_input("Clear all watch history? [y/N]:");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    if input.is_some_and(|input| input.trim().eq_ignore_ascii_case("y")) {
+                        if let Err(err) = history::clear() {
+                            view.set_error(&format!(
+                                "Could not clear watch history. Error: {}",
+                                err
+                            ));
+                            continue;
+                        }
+
+                        entries.clear();
+                        page_normal = Page::new(0, 3);
+                        finder.reset(&mut view);
+                    }
+                }
+                'e' => {
+                    view.clear_error();
+                    view.set_input("Export to JSON file:");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    let Some(input) = input.filter(|input| !input.is_empty()) else {
+                        continue;
+                    };
+
+                    let path = expand_path(&input);
+                    if let Err(err) = history::export(&path, &entries) {
+                        view.set_error(&format!(
+                            "Could not export watch history to '{}'. Error: {}",
+                            path.display(),
+                            err
+                        ));
+                    }
+                }
+                input => {
+                    view.set_error(&format!("{} is not a valid option!", input));
+                }
+            },
+            ViewInput::Num(num) => {
+                let Some(entry) = finder
+                    .page_or(&page_normal)
+                    .item_at_index(finder.videos_or(&entries), num)
+                else {
+                    view.set_error(&format!("{} is not a valid option!", num));
+                    continue;
+                };
+
+                // Only videos still in a subscription can be played from here
+                let video_id = channels.iter().find_map(|channel| {
+                    channel
+                        .videos
+                        .iter()
+                        .find(|video| video.id == entry.video_id)
+                        .map(|video| channel.video_id(video))
+                });
+
+                match video_id {
+                    Some(video_id) => return Message::Play(PlayType::Existing(video_id)),
+                    None => view.set_error("This video is no longer in your subscriptions"),
+                }
+            }
+        }
+    }
+}
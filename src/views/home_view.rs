@@ -1,16 +1,119 @@
+use std::{cmp::min, path::PathBuf};
+
 use crossterm::style::Stylize;
 
 use crate::{
+    groups::{Group, Groups},
+    importers::opml,
     page::Page,
+    utilities::{elapsed_formatted, expand_path},
     view::{Message, ViewPage},
     views::View,
-    yt::{ChannelIndex, Channels},
+    yt::{Channel, ChannelId, ChannelTab, Channels, RefreshStatus, SubscriptionKind},
 };
 
 use super::ViewInput;
 
-pub fn show(channels: &Channels) -> Message {
-    let mut page = Page::new(channels.len(), 1);
+/// A line on the home page, either a group or a channel
+enum HomeEntry<'a> {
+    Group(&'a Group, Vec<&'a Channel>),
+    Channel(&'a Channel, bool),
+}
+
+/// Groups first, each followed by its channels unless collapsed, then every channel not in a group
+fn home_entries<'a>(channels: &'a Channels, groups: &'a Groups) -> Vec<HomeEntry<'a>> {
+    let mut entries = Vec::new();
+
+    for group in groups.iter() {
+        let members: Vec<&Channel> = channels
+            .iter()
+            .filter(|channel| group.channel_ids.contains(&channel.id))
+            .collect();
+
+        entries.push(HomeEntry::Group(group, members.clone()));
+        if !group.collapsed {
+            entries.extend(
+                members
+                    .into_iter()
+                    .map(|channel| HomeEntry::Channel(channel, true)),
+            );
+        }
+    }
+
+    entries.extend(
+        channels
+            .iter()
+            .filter(|channel| groups.group_of(&channel.id).is_none())
+            .map(|channel| HomeEntry::Channel(channel, false)),
+    );
+    entries
+}
+
+fn channel_line(channel: &Channel) -> String {
+    let refreshed = match &channel.refresh {
+        RefreshStatus {
+            last_refreshed: Some(refreshed),
+            last_error: Some(err),
+        } => format!(
+            "Failed {}: {}",
+            elapsed_formatted(*refreshed),
+            err.lines().next().unwrap_or_default()
+        )
+        .red(),
+        RefreshStatus {
+            last_refreshed: Some(refreshed),
+            ..
+        } => format!("Refreshed {}", elapsed_formatted(*refreshed)).dark_grey(),
+        _ => "Never Refreshed".to_owned().dark_grey(),
+    };
+
+    let unseen = match channel.unseen_count() {
+        0 => String::new(),
+        count => format!(" ({} New)", count).cyan().bold().to_string(),
+    };
+
+    let kind = match channel.kind {
+        SubscriptionKind::Channel => String::new(),
+        SubscriptionKind::Playlist => format!(" {}", "[Playlist]".blue()),
+    };
+
+    format!(
+        "{}{}{}  {}",
+        channel.name.as_str().yellow(),
+        kind,
+        unseen,
+        refreshed
+    )
+}
+
+fn group_line(group: &Group, members: &[&Channel]) -> String {
+    let marker = if group.collapsed { "▸" } else { "▾" };
+    let channel_count = match members.len() {
+        1 => "1 Channel".to_owned(),
+        count => format!("{} Channels", count),
+    };
+    let unseen = match members.iter().map(|channel| channel.unseen_count()).sum() {
+        0 => String::new(),
+        count => format!(" ({} New)", count).cyan().bold().to_string(),
+    };
+
+    format!(
+        "{} {}{}  {}",
+        marker,
+        group.name.as_str().magenta().bold(),
+        unseen,
+        channel_count.dark_grey()
+    )
+}
+
+pub fn show(channels: &Channels, groups: &Groups, last_index: Option<usize>) -> Message {
+    let entries = home_entries(channels, groups);
+    let mut page = Page::new(entries.len(), 1);
+    // Collapsing a group can leave fewer entries than when the page was left
+    page.current_index = min(
+        last_index.unwrap_or(page.current_index),
+        entries.len().saturating_sub(page.count_per_page),
+    );
     let mut user = whoami::username();
 
     if let Some(first) = user.get_mut(0..1) {
@@ -27,7 +130,7 @@ pub fn show(channels: &Channels) -> Message {
 
     let mut view = View::new(
         format!("{} Home", user),
-        "(p)revious, (n)ext, (a)ll, (s)ubscribe, (v)ideo search, (r)efresh, (w)atch later, (q)uit"
+        "(p)revious, (n)ext, (a)ll, (u)nread, (g)roup feed, (s)ubscribe, (v)ideo search, (r)efresh, (w)atch later, (c)ontinue, (h)istory, (i)mport, (e)xport, (q)uit"
             .to_owned(),
         "▶".to_owned(),
     );
@@ -35,16 +138,16 @@ pub fn show(channels: &Channels) -> Message {
     loop {
         view.clear_content();
 
-        channels
+        page.current_page(&entries)
             .iter()
             .enumerate()
-            .map(|(i, video)| (i + page.current_index, video))
-            .for_each(|(i, channel)| {
-                view.add_line(format!(
-                    "{}. {}",
-                    i.to_string().green(),
-                    channel.name.as_str().yellow()
-                ))
+            .for_each(|(i, entry)| {
+                let line = match entry {
+                    HomeEntry::Group(group, members) => group_line(group, members),
+                    HomeEntry::Channel(channel, true) => format!("  {}", channel_line(channel)),
+                    HomeEntry::Channel(channel, false) => channel_line(channel),
+                };
+                view.add_line(format!("{}. {}", i.to_string().green(), line))
             });
 
         match view.show() {
@@ -54,8 +157,43 @@ pub fn show(channels: &Channels) -> Message {
                 's' => return Message::SearchChannels,
                 'v' => return Message::SearchVideosClean,
                 'w' => return Message::WatchLater,
+                'c' => return Message::ContinueWatching,
+                'h' => return Message::History,
                 'a' => return Message::MixedFeed(Some(page.current_index)),
-                'r' => return Message::Refresh(ViewPage::Home),
+                'u' => return Message::NewFeed(None),
+                'r' => return Message::Refresh(ViewPage::Home(Some(page.current_index))),
+                'g' => {
+                    view.clear_error();
+                    view.set_input("Open feed for group number:");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    let group = input
+                        .and_then(|input| input.trim().parse::<usize>().ok())
+                        .and_then(|num| page.item_at_index(&entries, num));
+
+                    match group {
+                        Some(HomeEntry::Group(group, _)) => {
+                            return Message::GroupFeed(group.name.clone(), None);
+                        }
+                        _ => view.set_error("Not a valid group number!"),
+                    }
+                }
+                'i' => return Message::Import,
+                'e' => {
+                    view.clear_error();
+                    let Some(path) = prompt_path("Export Subscriptions") else {
+                        continue;
+                    };
+
+                    if let Err(err) = opml::export(&path, channels) {
+                        view.set_error(&format!(
+                            "Could not export subscriptions to '{}'. Error: {}",
+                            path.display(),
+                            err
+                        ));
+                    }
+                }
                 'n' => {
                     page.next_page();
                     view.clear_error();
@@ -68,14 +206,36 @@ pub fn show(channels: &Channels) -> Message {
                     view.set_error(&format!("{} is not a valid option!", input));
                 }
             },
-            ViewInput::Num(num) => {
-                if page.item_is_at_index(num) {
+            ViewInput::Num(num) => match page.item_at_index(&entries, num) {
+                Some(HomeEntry::Group(group, _)) => {
+                    return Message::ToggleGroup(group.name.clone(), page.current_index);
+                }
+                Some(HomeEntry::Channel(channel, _)) => {
                     return Message::ChannelFeed(
-                        ChannelIndex(num + page.current_index),
+                        ChannelId::from(*channel),
+                        ChannelTab::Videos,
                         Some(page.current_index),
                     );
                 }
-            }
+                None => view.set_error(&format!("{} is not a valid option!", num)),
+            },
+        }
+    }
+}
+
+fn prompt_path(title: &str) -> Option<PathBuf> {
+    let mut view = View::new(
+        title.to_owned(),
+        "Esc(ape)".to_owned(),
+        "OPML File:".to_owned(),
+    );
+
+    loop {
+        let input = view.show_with_input()?;
+        if input.is_empty() {
+            view.set_error("File path can not be empty");
+        } else {
+            return Some(expand_path(&input));
         }
     }
 }
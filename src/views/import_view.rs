@@ -0,0 +1,133 @@
+use crossterm::style::Stylize;
+
+use crate::{
+    clear_screen,
+    config::Config,
+    importers::{self, ImportReport},
+    loading::run_while_loading,
+    page::Page,
+    utilities::expand_path,
+    view::Message,
+    yt::Channels,
+};
+
+use super::{View, ViewInput};
+
+pub fn show(channels: &Channels, config: &Config) -> Message {
+    let mut view = View::new(
+        "Import Subscriptions".to_owned(),
+        "Esc(ape)".to_owned(),
+        "File:".to_owned(),
+    );
+
+    view.add_line(format!(
+        "{}\n",
+        "Supported: OPML, Google Takeout (subscriptions.csv), NewPipe (subscriptions.json), FreeTube (profiles.db)"
+            .yellow()
+    ));
+
+    let report = loop {
+        let input = match view.show_with_input() {
+            Some(string) => string,
+            None => return Message::Home,
+        };
+
+        if input.is_empty() {
+            view.set_error("File path can not be empty");
+            continue;
+        }
+
+        let path = expand_path(&input);
+        let input_clone = input.clone();
+
+        let result = run_while_loading(
+            || importers::import(&path, channels, config),
+            move || {
+                println!("{}", "\nImport Subscriptions\n".cyan().bold());
+                print!("{} {}", "Importing:".green(), input_clone.as_str().yellow());
+            },
+        );
+
+        match result {
+            Ok(report) => break report,
+            Err(err) => view.set_error(&format!(
+                "Could not import subscriptions from '{}'. Error: {}",
+                input, err
+            )),
+        }
+    };
+
+    show_report(report)
+}
+
+fn show_report(report: ImportReport) -> Message {
+    let lines: Vec<String> = report
+        .channels
+        .iter()
+        .map(|channel| format!("{} {}", "+".green(), channel.name.as_str().yellow()))
+        .chain(report.skipped.iter().map(|issue| {
+            format!(
+                "{} {} ({})",
+                "-".dark_yellow(),
+                issue.name.as_str().yellow(),
+                issue.reason
+            )
+        }))
+        .chain(report.failed.iter().map(|issue| {
+            format!(
+                "{} {} ({})",
+                "!".red(),
+                issue.name.as_str().yellow(),
+                issue.reason.as_str().red()
+            )
+        }))
+        .collect();
+
+    let mut page = Page::new(lines.len(), 1);
+
+    let mut view = View::new(
+        format!(
+            "{} Import: {} To Subscribe, {} Skipped, {} Failed",
+            report.format.name(),
+            report.channels.len(),
+            report.skipped.len(),
+            report.failed.len()
+        ),
+        "(s)ubscribe, (p)revious, (n)ext, (b)ack".to_owned(),
+        "▶".to_owned(),
+    );
+
+    clear_screen();
+
+    loop {
+        view.clear_content();
+        view.update_page(Some(&page));
+
+        page.current_page(&lines)
+            .iter()
+            .for_each(|line| view.add_line(line.to_owned()));
+
+        match view.show() {
+            // Leaving without subscribing to anything
+            ViewInput::Esc | ViewInput::Char('b') => return Message::Import,
+            ViewInput::Char(char) => match char {
+                's' if report.channels.is_empty() => return Message::Home,
+                's' => return Message::SubscribeAll(report.channels),
+                'n' => {
+                    page.next_page();
+                    view.clear_error();
+                }
+                'p' => {
+                    page.prev_page();
+                    view.clear_error();
+                }
+                input => {
+                    view.set_error(&format!("{} is not a valid option!", input));
+                }
+            },
+            ViewInput::Num(num) => {
+                view.set_error(&format!("{} is not a valid option!", num));
+            }
+        }
+    }
+}
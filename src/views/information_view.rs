@@ -2,16 +2,23 @@ use crossterm::style::Stylize;
 
 use crate::{
     clear_screen,
+    config::Config,
     loading::run_while_loading,
     view::{LastView, Message, PlayType},
-    yt::{fetch_video_description, Channels, VideoIndex},
+    yt::{Channels, VideoId},
 };
 
 use super::{View, ViewInput};
 
-pub fn show(channels: &Channels, index: VideoIndex, last_view: LastView) -> Message {
-    let channel = channels.channel(index.into()).unwrap();
-    let video = channel.video(index).unwrap();
+pub fn show(
+    channels: &Channels,
+    video_id: &VideoId,
+    last_view: LastView,
+    config: &Config,
+) -> Message {
+    let Some((channel, video)) = channels.channel_video(video_id) else {
+        return last_view.as_ref().clone().into();
+    };
     let title = format!("\"{}\" - {}", video.title, channel.name);
 
     let mut view = View::new(
@@ -25,14 +32,14 @@ pub fn show(channels: &Channels, index: VideoIndex, last_view: LastView) -> Mess
     loop {
         match view.show() {
             ViewInput::Esc | ViewInput::Char('b') => {
-                return Message::Play(PlayType::Existing(index))
+                return Message::Play(PlayType::Existing(video_id.clone()))
             }
             ViewInput::Char(char) => match char {
                 'q' => return Message::Quit,
                 'm' => {
                     let title_moved = title.clone();
                     let results = run_while_loading(
-                        || fetch_video_description(video),
+                        || config.source().fetch_video_description(video),
                         move || {
                             println!("\n{}\n", title_moved.as_str().bold().cyan());
                             print!("{} ", "Fetching more information".green());
@@ -44,7 +51,11 @@ pub fn show(channels: &Channels, index: VideoIndex, last_view: LastView) -> Mess
                             view.add_line(format!("{}\n", "Description:".yellow()));
                             view.add_line(new_description.clone());
                             clear_screen();
-                            return Message::MoreInformation(index, last_view, new_description);
+                            return Message::MoreInformation(
+                                video_id.clone(),
+                                last_view,
+                                new_description,
+                            );
                         }
                         Err(e) => {
                             view.set_error(&format!(
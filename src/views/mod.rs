@@ -10,10 +10,14 @@ use crossterm::{
 
 use crate::{clear_screen, page::Page};
 
+pub mod continue_view;
 pub mod feed_view;
+pub mod history_view;
 pub mod home_view;
+pub mod import_view;
 pub mod information_view;
 pub mod player_view;
+pub mod refresh_view;
 pub mod search_channel_view;
 pub mod search_video_view;
 pub mod watch_later_view;
@@ -47,6 +51,10 @@ impl View {
         }
     }
 
+    pub fn set_input(&mut self, input: &str) {
+        self.input = input.to_owned();
+    }
+
     pub fn update_page(&mut self, page: Option<&Page>) {
         self.pages_progress = page.map(|page| (page.page_current(), page.pages_count()));
     }
@@ -80,17 +88,17 @@ impl View {
             println!();
         }
 
-        if let Some((current, total)) = self.pages_progress {
-            if total > 0 {
-                println!(
-                    "{}{}{}{}{}\n",
-                    "Page: [".yellow(),
-                    current.to_string().dark_yellow(),
-                    "/".yellow(),
-                    total.to_string().yellow(),
-                    "]".yellow()
-                )
-            }
+        if let Some((current, total)) = self.pages_progress
+            && total > 0
+        {
+            println!(
+                "{}{}{}{}{}\n",
+                "Page: [".yellow(),
+                current.to_string().dark_yellow(),
+                "/".yellow(),
+                total.to_string().yellow(),
+                "]".yellow()
+            )
         }
 
         println!("{}\n", self.options.as_str().green().italic());
@@ -152,17 +160,17 @@ impl View {
             println!();
         }
 
-        if let Some((current, total)) = self.pages_progress {
-            if total > 0 {
-                println!(
-                    "{}{}{}{}{}\n",
-                    "Page: [".yellow(),
-                    current.to_string().dark_yellow(),
-                    "/".yellow(),
-                    total.to_string().yellow(),
-                    "]".yellow()
-                )
-            }
+        if let Some((current, total)) = self.pages_progress
+            && total > 0
+        {
+            println!(
+                "{}{}{}{}{}\n",
+                "Page: [".yellow(),
+                current.to_string().dark_yellow(),
+                "/".yellow(),
+                total.to_string().yellow(),
+                "]".yellow()
+            )
         }
 
         println!("{}\n", self.options.as_str().green().italic());
@@ -2,6 +2,7 @@ use std::{
     process::{Command, Stdio},
     rc::Rc,
     thread,
+    time::Duration,
 };
 
 use crossterm::style::Stylize;
@@ -11,8 +12,9 @@ use crate::{
     loading::{cmd_while_loading, cmd_while_loading_with_background, run_while_loading},
     log,
     mpv::{WatchProgress, MPV_SOCKET},
+    progress::ProgressStore,
     view::{Error, Message, PlayType, ViewPage},
-    yt::{fetch_channel_feed, Channel, Channels, Video, VideoInfo, VideoWatchLater},
+    yt::{Channel, Channels, Video, VideoInfo, VideoWatchLater},
 };
 
 use super::{View, ViewInput};
@@ -20,21 +22,29 @@ use super::{View, ViewInput};
 pub fn show(
     channels: &Channels,
     watch_later: &[VideoWatchLater],
+    progress: &ProgressStore,
     play_type: &PlayType,
     last_view: &ViewPage,
     config: &Config,
 ) -> Message {
     let (url, title, progress_before, mut view) = match &play_type {
-        PlayType::Existing(video_index) => {
-            let channel = channels.channel((*video_index).into()).unwrap();
-            let video = channel.video(*video_index).unwrap();
+        PlayType::Existing(video_id) => {
+            // Removed by a background refresh or unsubscribe since it was picked
+            let Some((channel, video)) = channels.channel_video(video_id) else {
+                return last_view.clone().into();
+            };
             let view = View::new(
                 format!("\"{}\" - {}", video.title, channel.name),
                 "(p)lay, (d)etach, (s)ave, (P)lay + save, (i)nformation, (b)ack, (q)uit".to_owned(),
                 "▶".to_owned(),
             );
 
-            (video.url(), video.title.clone(), video.progress, view)
+            (
+                video.url(),
+                video.title.clone(),
+                progress.get(&video.id),
+                view,
+            )
         }
         PlayType::New(video_info, _) => {
             let view = View::new(
@@ -43,10 +53,17 @@ pub fn show(
                 "▶".to_owned(),
             );
 
-            (video_info.url(), video_info.title.clone(), None, view)
+            (
+                video_info.url(),
+                video_info.title.clone(),
+                progress.get(&video_info.id),
+                view,
+            )
         }
-        PlayType::WatchLater(index) => {
-            let later = watch_later.get(*index).unwrap();
+        PlayType::WatchLater(video_id) => {
+            let Some(later) = watch_later.iter().find(|later| later.is(video_id)) else {
+                return last_view.clone().into();
+            };
             let view = View::new(
                 format!("\"{}\" - {}", later.video.title, later.channel.name),
                 "(p)lay, (d)etach, (s)ave, (P)lay + save, (r)emove, (b)ack, (q)uit".to_owned(),
@@ -56,7 +73,7 @@ pub fn show(
             (
                 later.video.url(),
                 later.video.title.clone(),
-                later.video.progress,
+                progress.get(&later.video.id),
                 view,
             )
         }
@@ -73,13 +90,13 @@ pub fn show(
             ViewInput::Char(char) => match char {
                 'q' => return Message::Quit,
                 'i' => {
-                    if let PlayType::Existing(index) = play_type {
-                        return Message::Information(*index, Rc::new(last_view.clone()));
+                    if let PlayType::Existing(video_id) = play_type {
+                        return Message::Information(video_id.clone(), Rc::new(last_view.clone()));
                     } else {
                         view.set_error("i is not a valid option!");
                     }
                 }
-                'p' => match play(&title, &url, progress_before.as_ref()) {
+                'p' => match play(&title, &url, play_progress.or(progress_before).as_ref()) {
                     Err(Error::CommandFailed(e)) => {
                         view.set_error(&format!("Could not run play command: mpv.\nError: {}", e));
                     }
@@ -91,7 +108,12 @@ pub fn show(
                     }
                     _ => panic!(),
                 },
-                'P' => match play_and_download(&title, &url, config, progress_before.as_ref()) {
+                'P' => match play_and_download(
+                    &title,
+                    &url,
+                    config,
+                    play_progress.or(progress_before).as_ref(),
+                ) {
                     Err(Error::CommandFailed(e)) => {
                         view.set_error(&format!("Could not play video\nError: {}", e));
                     }
@@ -125,19 +147,11 @@ pub fn show(
                 }
                 'b' => {
                     if let Some(progress) = play_progress {
-                        if let PlayType::Existing(index) = play_type {
-                            return Message::Played(
-                                Rc::new(last_view.clone()),
-                                Some(*index),
-                                Some(progress),
-                            );
-                        } else {
-                            return Message::Played(
-                                Rc::new(last_view.clone()),
-                                None,
-                                Some(progress),
-                            );
-                        }
+                        return Message::Played(
+                            Rc::new(last_view.clone()),
+                            play_type.clone(),
+                            Some(progress),
+                        );
                     }
                     return last_view.to_owned().into();
                 }
@@ -154,12 +168,16 @@ pub fn show(
                     if let PlayType::New(info, _) = play_type {
                         //TODO: Add description to WatchInfo
                         let later = VideoWatchLater {
-                            video: Video::new(
-                                info.title.clone(),
-                                info.id.clone(),
-                                "N/A",
-                                info.upload,
-                            ),
+                            video: Video {
+                                duration: info.duration,
+                                view_count: info.view_count,
+                                ..Video::new(
+                                    info.title.clone(),
+                                    info.id.clone(),
+                                    "N/A",
+                                    info.upload,
+                                )
+                            },
                             channel: info.channel.clone(),
                         };
                         return Message::WatchLaterAdd(later, Rc::new(last_view.to_owned()));
@@ -168,8 +186,8 @@ pub fn show(
                     }
                 }
                 'r' => {
-                    if let PlayType::WatchLater(index) = play_type {
-                        return Message::WatchLaterRemove(*index);
+                    if let PlayType::WatchLater(video_id) = play_type {
+                        return Message::WatchLaterRemove(video_id.clone());
                     } else {
                         view.set_error("r is not a valid option!");
                     }
@@ -188,7 +206,11 @@ pub fn show(
 fn subscribe(view: &mut View, info: &VideoInfo, config: &Config) -> Option<Message> {
     let name = info.channel.name.clone();
     let feed = run_while_loading(
-        || fetch_channel_feed(&info.channel.id, config.videos_per_channel, None),
+        || {
+            config
+                .source()
+                .fetch_channel_feed(&info.channel.id, config.videos_per_channel, None)
+        },
         move || {
             println!("{}", "\nNew Subscriptions\n".cyan().bold());
             print!(
@@ -221,7 +243,12 @@ fn subscribe(view: &mut View, info: &VideoInfo, config: &Config) -> Option<Messa
                     info.channel.name, e
                 ));
             }
-            _ => panic!(),
+            err => {
+                view.set_error(&format!(
+                    "Could not load in feed for channel: '{}'.\nError: {}",
+                    info.channel.name, err
+                ));
+            }
         },
     }
     None
@@ -248,7 +275,9 @@ fn play_and_download(
             .output()
         {
             Ok(result) => {
-                if let Ok(err) = String::from_utf8(result.stderr) && !err.is_empty() {
+                if let Ok(err) = String::from_utf8(result.stderr)
+                    && !err.is_empty()
+                {
                     log::err(err)
                 }
             }
@@ -286,25 +315,54 @@ fn play(
     progress: Option<&WatchProgress>,
 ) -> Result<Option<WatchProgress>, Error> {
     let title = title.to_owned();
-    let url = url.to_owned();
 
     cmd_while_loading_with_background(
-        Command::new("mpv")
-            .arg(url)
-            .arg("--ytdl-raw-options=remote-components=ejs:github")
-            .arg(format!("{}{}", "--input-ipc-server=", MPV_SOCKET))
-            .arg(format!(
-                "{}{}",
-                "--start=",
-                progress.map(|progress| progress.current).unwrap_or(0)
-            ))
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn(),
+        mpv_command(url, progress).spawn(),
         move || {
             print!("\r\n{}\r\n\r\n", title.as_str().cyan().bold());
             print!("{} '{}'", "Playing ".green(), title.as_str().yellow());
         },
-        Some(Box::new(|| WatchProgress::playing())),
+        Some(Box::new(WatchProgress::playing)),
     )
 }
+
+/// Plays a video without drawing any views, blocking until mpv exits
+pub fn play_headless(
+    url: &str,
+    progress: Option<&WatchProgress>,
+) -> Result<Option<WatchProgress>, Error> {
+    let mut command = mpv_command(url, progress)
+        .spawn()
+        .map_err(|e| Error::CommandFailed(e.to_string()))?;
+
+    let mut last_progress = None;
+
+    loop {
+        match command.try_wait() {
+            Ok(Some(_)) => return Ok(last_progress),
+            Ok(None) => {
+                if let Some(progress) = WatchProgress::playing() {
+                    last_progress = Some(progress);
+                }
+                thread::sleep(Duration::from_secs(1));
+            }
+            Err(e) => return Err(Error::CommandFailed(e.to_string())),
+        }
+    }
+}
+
+fn mpv_command(url: &str, progress: Option<&WatchProgress>) -> Command {
+    let mut command = Command::new("mpv");
+    command
+        .arg(url)
+        .arg("--ytdl-raw-options=remote-components=ejs:github")
+        .arg(format!("{}{}", "--input-ipc-server=", MPV_SOCKET))
+        .arg(format!(
+            "{}{}",
+            "--start=",
+            progress.map(|progress| progress.current).unwrap_or(0)
+        ))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
@@ -0,0 +1,120 @@
+use std::cmp::Reverse;
+
+use crossterm::style::Stylize;
+
+use crate::{
+    clear_screen,
+    page::Page,
+    updates::RefreshReport,
+    view::{LastView, Message},
+    yt::{ChannelInfo, ChannelTab, Channels},
+};
+
+use super::{View, ViewInput};
+
+pub fn show(report: &RefreshReport, channels: &Channels, last_view: &LastView) -> Message {
+    let mut updated: Vec<_> = report.updated.iter().collect();
+    updated.sort_by_key(|update| Reverse(update.new_videos));
+
+    // Channels with new videos first, then failures, with each line's channel kept alongside for jumping to its feed
+    let entries: Vec<(&ChannelInfo, String)> = updated
+        .iter()
+        .map(|update| {
+            let new_videos = match update.new_videos {
+                0 => "No New Videos".to_owned().dark_grey(),
+                1 => "1 New Video".to_owned().green(),
+                count => format!("{} New Videos", count).green(),
+            };
+            (
+                &update.channel,
+                format!("{}  {}", update.channel.name.as_str().yellow(), new_videos),
+            )
+        })
+        .chain(report.failed.iter().map(|failure| {
+            (
+                &failure.channel,
+                format!(
+                    "{}  {}",
+                    failure.channel.name.as_str().yellow(),
+                    failure.reason.as_str().red()
+                ),
+            )
+        }))
+        .collect();
+
+    let mut page = Page::new(entries.len(), 1);
+
+    let mut title = format!(
+        "Refresh: {} Updated, {} New Videos, {} Failed",
+        report.updated.len(),
+        report.new_videos(),
+        report.failed.len()
+    );
+    if report.skipped > 0 {
+        title.push_str(&format!(", {} Cancelled", report.skipped));
+    }
+
+    let mut view = View::new(
+        title,
+        "(p)revious, (n)ext, (r)etry failed, (b)ack".to_owned(),
+        "▶".to_owned(),
+    );
+
+    clear_screen();
+
+    loop {
+        view.clear_content();
+        view.update_page(Some(&page));
+
+        page.current_page(&entries)
+            .iter()
+            .enumerate()
+            .for_each(|(i, (_, line))| {
+                view.add_line(format!("{}. {}", i.to_string().green(), line))
+            });
+
+        match view.show() {
+            ViewInput::Esc | ViewInput::Char('b') => return last_view.as_ref().clone().into(),
+            ViewInput::Char(char) => match char {
+                'r' => {
+                    if report.failed.is_empty() {
+                        view.set_error("No channels failed to refresh");
+                        continue;
+                    }
+
+                    return Message::RefreshChannels(
+                        report
+                            .failed
+                            .iter()
+                            .map(|failure| failure.channel.clone())
+                            .collect(),
+                        last_view.as_ref().clone(),
+                    );
+                }
+                'n' => {
+                    page.next_page();
+                    view.clear_error();
+                }
+                'p' => {
+                    page.prev_page();
+                    view.clear_error();
+                }
+                input => {
+                    view.set_error(&format!("{} is not a valid option!", input));
+                }
+            },
+            ViewInput::Num(num) => {
+                let channel = page
+                    .item_at_index(&entries, num)
+                    .and_then(|(channel, _)| channels.channel_by_id(&channel.id));
+
+                match channel {
+                    Some(channel) => {
+                        return Message::ChannelFeed(channel.into(), ChannelTab::Videos, None)
+                    }
+                    None => view.set_error(&format!("{} is not a valid option!", num)),
+                }
+            }
+        }
+    }
+}
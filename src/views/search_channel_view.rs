@@ -5,9 +5,8 @@ use crate::{
     config::Config,
     loading::run_while_loading,
     page::Page,
-    search::fetch_channel,
-    view::Message,
-    yt::{fetch_channel_feed, Channel, Channels},
+    view::{Error, Message},
+    yt::{playlist_id_from_url, Channel, Channels},
 };
 
 use super::{View, ViewInput};
@@ -16,32 +15,40 @@ pub fn show(channels: &Channels, config: &Config) -> Message {
     let mut view = View::new(
         "New Subscriptions".to_owned(),
         "Esc(ape)".to_owned(),
-        "Search:".to_owned(),
+        "Search or playlist URL:".to_owned(),
     );
 
-    let mut input;
-
-    loop {
-        input = match view.show_with_input() {
+    let results = loop {
+        let input = match view.show_with_input() {
             Some(string) => string,
             None => return Message::Home,
         };
         if input.is_empty() {
             view.set_error("Search query can not be empty");
-        } else {
-            break;
+            continue;
+        } else if playlist_id_from_url(&input).is_some() {
+            match subscribe_playlist(&input, channels, config) {
+                Ok(playlist) => return Message::Subscribe(playlist),
+                Err(err) => view.set_error(&err),
+            }
+            continue;
         }
-    }
 
-    let input_clone = input.clone();
+        let input_clone = input.clone();
 
-    let results = run_while_loading(
-        || fetch_channel(&input, 20),
-        move || {
-            println!("{}", "\nNew Subscriptions\n".to_string().cyan().bold());
-            print!("{} {}", "Searching:".green(), input_clone.as_str().yellow());
-        },
-    );
+        let results = run_while_loading(
+            || config.source().fetch_channels(&input, 20),
+            move || {
+                println!("{}", "\nNew Subscriptions\n".to_string().cyan().bold());
+                print!("{} {}", "Searching:".green(), input_clone.as_str().yellow());
+            },
+        );
+
+        match results {
+            Ok(results) => break results,
+            Err(err) => view.set_error(&format!("Could not search for channels.\nError: {}", err)),
+        }
+    };
 
     let mut page = Page::new(results.len(), 1);
 
@@ -87,7 +94,7 @@ pub fn show(channels: &Channels, config: &Config) -> Message {
             },
             ViewInput::Num(num) => {
                 let Some(channel) = page.item_at_index(&results, num) else {
-                    view.set_error(&format!("{} is not a valid option!", input));
+                    view.set_error(&format!("{} is not a valid option!", num));
                     continue;
                 };
 
@@ -98,7 +105,13 @@ pub fn show(channels: &Channels, config: &Config) -> Message {
 
                 let name = channel.name.clone();
                 let feed = run_while_loading(
-                    || fetch_channel_feed(&channel.id, config.videos_per_channel, None),
+                    || {
+                        config.source().fetch_channel_feed(
+                            &channel.id,
+                            config.videos_per_channel,
+                            None,
+                        )
+                    },
                     move || {
                         println!("{}", "\nNew Subscriptions\n".cyan().bold());
                         print!(
@@ -110,13 +123,7 @@ pub fn show(channels: &Channels, config: &Config) -> Message {
                 );
 
                 match feed {
-                    Ok(feed) => {
-                        return Message::Subscribe(Channel::new(
-                            channel.name.as_str(),
-                            channel.id.as_str(),
-                            feed,
-                        ))
-                    }
+                    Ok(feed) => return Message::Subscribe(Channel::from_info(channel, feed)),
                     Err(err) => {
                         view.set_error(&format!(
                             "Could not load in feed for channel: '{}' with command 'yt-dlp'.\nError: {}",
@@ -128,3 +135,41 @@ pub fn show(channels: &Channels, config: &Config) -> Message {
         }
     }
 }
+
+/// Looks up a playlist by its URL and downloads its videos
+fn subscribe_playlist(url: &str, channels: &Channels, config: &Config) -> Result<Channel, String> {
+    let url_moved = url.to_owned();
+    let playlist = run_while_loading(
+        || {
+            let info = config.source().fetch_playlist_from_url(url)?;
+            let feed =
+                config
+                    .source()
+                    .fetch_feed(info.kind, &info.id, config.videos_per_channel, None);
+            Ok((info, feed))
+        },
+        move || {
+            println!("{}", "\nNew Subscriptions\n".cyan().bold());
+            print!(
+                "{} {}",
+                "Downloading videos for".green(),
+                url_moved.as_str().yellow()
+            );
+        },
+    );
+
+    let (info, feed) = playlist
+        .map_err(|err: Error| format!("Could not find playlist '{}'.\nError: {}", url, err))?;
+
+    if channels.has_channel(&info.id) {
+        return Err(format!("You are already subscribed to {}!", info.name));
+    }
+
+    feed.map(|feed| Channel::from_info(&info, feed))
+        .map_err(|err| {
+            format!(
+                "Could not load in feed for playlist: '{}' with command 'yt-dlp'.\nError: {}",
+                info.name, err
+            )
+        })
+}
@@ -1,20 +1,26 @@
 use std::rc::Rc;
 
 use crossterm::style::Stylize;
+use itertools::Itertools;
 
 use crate::{
     clear_screen,
     config::Config,
+    filters::Filters,
     loading::run_while_loading,
     page::Page,
-    search::fetch_videos,
-    utilities::time_since_formatted,
-    view::{LastSearch, Message, PlayType},
+    utilities::entry_line_formatted,
+    view::{LastSearch, Message, PlayType, Visibility},
 };
 
 use super::{View, ViewInput};
 
-pub fn show(config: &Config, cached_search: Option<&LastSearch>) -> Message {
+pub fn show(
+    config: &Config,
+    cached_search: Option<&LastSearch>,
+    filters: &Filters,
+    visibility: Visibility,
+) -> Message {
     let mut view = View::new(
         "Video Search".to_owned(),
         "Esc(ape)".to_owned(),
@@ -22,45 +28,57 @@ pub fn show(config: &Config, cached_search: Option<&LastSearch>) -> Message {
     );
 
     // Wrap new data || old data in either a clone of existing Rc, or new Rc
+    let searched;
     let search_shared_cached = {
         if let Some(cached) = &cached_search {
             cached
         } else {
-            let mut input;
-            loop {
-                input = match view.show_with_input() {
+            searched = loop {
+                let input = match view.show_with_input() {
                     Some(string) => string,
                     None => return Message::Home,
                 };
                 if input.is_empty() {
                     view.set_error("Search query can not be empty");
-                } else {
-                    break;
+                    continue;
                 }
-            }
-
-            let input_clone = input.clone();
-
-            let results = run_while_loading(
-                || fetch_videos(&input, config.videos_per_search),
-                move || {
-                    println!("{}", "\nVideo Search\n".to_string().cyan().bold());
-                    print!("{} {}", "Searching:".green(), input_clone.as_str().yellow());
-                },
-            );
 
-            &Rc::new((results, input))
+                let input_clone = input.clone();
+
+                let results = run_while_loading(
+                    || {
+                        config
+                            .source()
+                            .fetch_videos(&input, config.videos_per_search)
+                    },
+                    move || {
+                        println!("{}", "\nVideo Search\n".to_string().cyan().bold());
+                        print!("{} {}", "Searching:".green(), input_clone.as_str().yellow());
+                    },
+                );
+
+                match results {
+                    Ok(results) => break Rc::new((results, input)),
+                    Err(err) => {
+                        view.set_error(&format!("Could not search for videos.\nError: {}", err))
+                    }
+                }
+            };
+            &searched
         }
     };
 
-    let results = &search_shared_cached.0;
     let input = &search_shared_cached.1;
-
+    let results = search_shared_cached
+        .0
+        .iter()
+        .filter(|video| visibility.show_hidden || !filters.hides(&video.title, video.duration))
+        .collect_vec();
     let mut page = Page::new(results.len(), 3);
 
     let mut view = View::new(
         format!("Videos for '{}'", &input),
-        "(p)revious, (n)ext, b(ack), q(uit)".to_owned(),
+        "(p)revious, (n)ext, (H)idden, b(ack), q(uit)".to_owned(),
         "▶".to_owned(),
     );
 
@@ -70,16 +88,22 @@ pub fn show(config: &Config, cached_search: Option<&LastSearch>) -> Message {
         view.clear_content();
         view.update_page(Some(&page));
 
-        page.current_page(results)
+        page.current_page(&results)
             .iter()
             .enumerate()
             .for_each(|(i, video)| {
+                let details = entry_line_formatted(
+                    Some(&video.channel.name),
+                    video.upload,
+                    video.details(),
+                    None,
+                );
+
                 view.add_line(format!(
-                    "{}. {}\n   {} • {}\n",
+                    "{}. {}\n   {}\n",
                     i.to_string().green(),
                     video.title.as_str().dark_yellow(),
-                    video.channel.name,
-                    time_since_formatted(video.upload),
+                    details
                 ));
             });
 
@@ -95,18 +119,19 @@ pub fn show(config: &Config, cached_search: Option<&LastSearch>) -> Message {
                     page.prev_page();
                     view.clear_error();
                 }
+                'H' => return Message::ToggleShowHiddenSearch(search_shared_cached.clone()),
                 input => {
                     view.set_error(&format!("{} is not a valid option!", input));
                 }
             },
             ViewInput::Num(num) => {
-                let Some(video) = page.item_at_index(results, num) else {
+                let Some(video) = page.item_at_index(&results, num) else {
                     view.set_error(&format!("{} is not a valid option!", input));
                     continue;
                 };
 
                 return Message::Play(PlayType::New(
-                    video.to_owned(),
+                    (*video).to_owned(),
                     Some(search_shared_cached.clone()),
                 ));
             }
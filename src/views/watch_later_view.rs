@@ -4,8 +4,8 @@ use itertools::Itertools;
 use crate::{
     finder::Finder,
     page::Page,
-    utilities::{highlight_query, time_since_formatted},
-    view::{Message, PlayType},
+    utilities::{entry_line_formatted, highlight_query},
+    view::{Message, PlayType, ViewPage, Visibility},
     views::ViewInput,
     yt::{Video, VideoWatchLater},
 };
@@ -13,20 +13,29 @@ use crate::{
 use super::View;
 
 fn get_title_formatted(video: &Video, query: Option<&str>) -> String {
-    if video.progress.is_some() {
+    if video.watched {
+        highlight_query(&video.title, query, Some(Color::DarkGrey))
+    } else if video.progress.is_some() {
         highlight_query(&video.title, query, Some(Color::Yellow))
     } else {
         highlight_query(&video.title, query, Some(Color::DarkYellow))
     }
 }
 
-pub fn show(watch_later: &Vec<VideoWatchLater>) -> Message {
+pub fn show(watch_later: &[VideoWatchLater], visibility: Visibility) -> Message {
     let mut view = View::new(
         "Watch Later".to_owned(),
-        "(p)revious, (n)ext, (f)ind, (b)ack, (q)uit".to_owned(),
+        "(p)revious, (n)ext, (f)ind, (w)atched, (H)idden, (b)ack, (q)uit".to_owned(),
         "▶".to_owned(),
     );
 
+    // Watched videos stay listed until removed, so only muted ones are left out
+    let watch_later = &watch_later
+        .iter()
+        .filter(|later| visibility.show_hidden || !later.video.hidden)
+        .cloned()
+        .collect_vec();
+
     let mut page_normal = Page::new(watch_later.len(), 3);
     let mut finder = Finder::new(watch_later.len(), 3);
 
@@ -41,18 +50,18 @@ pub fn show(watch_later: &Vec<VideoWatchLater>) -> Message {
             .enumerate();
 
         iter.for_each(|(i, entry)| {
+            let details = entry_line_formatted(
+                Some(&entry.channel.name),
+                entry.video.upload,
+                entry.video.details(),
+                Some(entry.video.watch_status()),
+            );
+
             let line = format!(
-                "{}. {}\n   {} • {} • {}\n",
+                "{}. {}\n   {}\n",
                 i.to_string().green(),
-                get_title_formatted(&entry.video, finder.query()),
-                entry.channel.name,
-                time_since_formatted(entry.video.upload),
-                entry
-                    .video
-                    .progress
-                    .as_ref()
-                    .map(|progress| progress.formatted())
-                    .unwrap_or("Not Watched".to_owned())
+                get_title_formatted(&entry.video, finder.terms()),
+                details
             );
             view.add_line(line);
         });
@@ -73,6 +82,7 @@ pub fn show(watch_later: &Vec<VideoWatchLater>) -> Message {
             ViewInput::Char(char) => match char {
                 'q' => return Message::Quit,
                 'b' => return Message::Home,
+                'H' => return Message::ToggleShowHidden(ViewPage::WatchLater),
                 'n' => {
                     page.next_page();
                     view.clear_error();
@@ -81,6 +91,31 @@ pub fn show(watch_later: &Vec<VideoWatchLater>) -> Message {
                     page.prev_page();
                     view.clear_error();
                 }
+                'w' => {
+                    view.clear_error();
+                    view.set_input("Toggle watched for video:");
+                    let input = view.show_with_input();
+                    view.set_input("▶");
+
+                    let item = input
+                        .and_then(|input| input.trim().parse::<usize>().ok())
+                        .and_then(|num| {
+                            finder
+                                .page_or(&page_normal)
+                                .item_at_index(finder.videos_or(watch_later), num)
+                        });
+
+                    match item {
+                        Some(later) => {
+                            return Message::MarkWatched(
+                                later.video.id.clone(),
+                                !later.video.watched,
+                                ViewPage::WatchLater,
+                            );
+                        }
+                        None => view.set_error("Not a valid video number!"),
+                    }
+                }
                 'f' => {
                     view.clear_error();
                     let Some(input) = view.show_with_input() else {
@@ -88,29 +123,24 @@ pub fn show(watch_later: &Vec<VideoWatchLater>) -> Message {
                         continue;
                     };
 
-                    let filtered = watch_later
-                        .iter()
-                        .filter(|video| {
-                            video
-                                .video
-                                .title
-                                .to_lowercase()
-                                .contains(&input.to_lowercase())
-                        })
-                        .cloned()
-                        .collect_vec();
-
-                    finder.update(&mut view, filtered, &input.to_lowercase());
+                    if let Err(err) = finder.search(&mut view, watch_later, &input) {
+                        view.set_error(&err);
+                    }
                 }
                 input => {
                     view.set_error(&format!("{} is not a valid option!", input));
                 }
             },
             ViewInput::Num(num) => {
-                if page.item_is_at_index(num) {
-                    return Message::Play(PlayType::WatchLater(page.current_index + num));
-                } else {
-                    view.set_error(&format!("{} is not a valid option!", num));
+                let item = finder
+                    .page_or(&page_normal)
+                    .item_at_index(finder.videos_or(watch_later), num);
+
+                match item {
+                    Some(later) => {
+                        return Message::Play(PlayType::WatchLater(later.video_id()));
+                    }
+                    None => view.set_error(&format!("{} is not a valid option!", num)),
                 }
             }
         }
@@ -1,7 +1,7 @@
-use core::panic;
 use std::{
+    cmp::Reverse,
+    collections::HashSet,
     ops::{Deref, DerefMut},
-    process::Command,
 };
 
 use chrono::{DateTime, Local};
@@ -9,19 +9,67 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{cache, config::Config, log, mpv::WatchProgress, view::Error};
+use crate::{
+    cache,
+    config::Config,
+    filters::Filters,
+    log,
+    mpv::WatchProgress,
+    progress::ProgressStore,
+    utilities::{details_formatted, seconds_formatted},
+    view::Error,
+};
 
 #[derive(Debug, Clone)]
 pub struct Channel {
     pub name: String,
     pub id: String,
+    pub kind: SubscriptionKind,
     pub videos: Vec<Video>,
+    /// Scheduled premieres and livestreams, soonest first. Moved into `videos` once a refresh finds them live
+    pub upcoming: Vec<Video>,
+    pub refresh: RefreshStatus,
+    /// Set from the filter rules, never saved
+    pub excluded_from_mixed: bool,
+    known: HashSet<String>,
+}
+
+/// When a channel was last refreshed and why that refresh failed, if it did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RefreshStatus {
+    pub last_refreshed: Option<DateTime<Local>>,
+    pub last_error: Option<String>,
+}
+
+/// Whether a subscription follows a whole channel or a single playlist
+#[derive(
+    Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubscriptionKind {
+    #[default]
+    Channel,
+    Playlist,
+}
+
+/// The tab of a channel's page a video is listed under. Only videos are fetched unless other tabs are enabled
+#[derive(
+    Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChannelTab {
+    #[default]
+    Videos,
+    Streams,
+    Shorts,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct ChannelInfo {
     pub id: String,
     pub name: String,
+    #[serde(default)]
+    pub kind: SubscriptionKind,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
@@ -30,6 +78,11 @@ pub struct VideoInfo {
     pub title: String,
     pub channel: ChannelInfo,
     pub upload: DateTime<Local>,
+    /// In seconds
+    #[serde(default)]
+    pub duration: Option<u32>,
+    #[serde(default)]
+    pub view_count: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, PartialOrd, Ord, Hash)]
@@ -47,7 +100,31 @@ pub struct Video {
     pub id: String,
     pub upload: DateTime<Local>,
     pub description: String,
+    /// Copied from the progress store on load. Only read from caches to migrate older versions
+    #[serde(default, skip_serializing)]
     pub progress: Option<WatchProgress>,
+    /// Videos cached before unread tracking existed count as seen
+    #[serde(default = "seen_default")]
+    pub seen: bool,
+    #[serde(default)]
+    pub watched: bool,
+    #[serde(default)]
+    pub tab: ChannelTab,
+    /// Not started yet, with `upload` as the scheduled start
+    #[serde(default)]
+    pub upcoming: bool,
+    /// In seconds. Missing for videos cached before it was tracked and from sources that do not list it
+    #[serde(default)]
+    pub duration: Option<u32>,
+    #[serde(default)]
+    pub view_count: Option<u64>,
+    /// Muted by the filter rules. Set on load and whenever videos are added, never saved
+    #[serde(skip)]
+    pub hidden: bool,
+}
+
+fn seen_default() -> bool {
+    true
 }
 
 #[derive(Default)]
@@ -58,32 +135,62 @@ pub struct VideoAccumulator {
     decription: Option<String>,
     available: bool,
     is_short: bool,
+    live_status: Option<String>,
+    release: Option<DateTime<Local>>,
+    duration: Option<u32>,
+    view_count: Option<u64>,
+    tab: ChannelTab,
 }
 
-#[derive(Clone, Copy)]
-pub struct VideoIndex {
-    pub channel_index: usize,
-    pub video_index: usize,
+/// A subscribed channel's video by ID, so it stays correct while channels and videos are added, removed or re-sorted
+#[derive(Clone)]
+pub struct VideoId {
+    pub channel_id: String,
+    pub video_id: String,
 }
 
-#[derive(Clone, Copy)]
-pub struct ChannelIndex(pub usize);
+/// A subscribed channel by ID
+#[derive(Clone)]
+pub struct ChannelId(pub String);
 
 impl Channels {
     pub fn new(channels_cached: &[ChannelInfo]) -> Channels {
+        let mut statuses = cache::fetch_refresh_statuses();
+        let mut upcoming = cache::fetch_upcoming_videos();
+
         Channels(
             channels_cached
                 .iter()
                 .filter_map(|cached: &ChannelInfo| cache::load_channel(cached).ok())
+                .map(|mut channel| {
+                    channel.refresh = statuses.remove(&channel.id).unwrap_or_default();
+                    channel.upcoming = upcoming.remove(&channel.id).unwrap_or_default();
+                    channel
+                })
                 .collect::<Vec<Channel>>(),
         )
     }
-    pub fn channel_mut(&mut self, channel_index: ChannelIndex) -> Option<&mut Channel> {
-        self.get_mut(*channel_index)
+    pub fn channel_mut(&mut self, channel_id: &ChannelId) -> Option<&mut Channel> {
+        self.channel_by_id_mut(channel_id)
+    }
+
+    pub fn channel(&self, channel_id: &ChannelId) -> Option<&Channel> {
+        self.channel_by_id(channel_id)
+    }
+
+    pub fn video(&self, video_id: &VideoId) -> Option<&Video> {
+        self.channel_by_id(&video_id.channel_id)?.video(video_id)
+    }
+
+    pub fn video_mut(&mut self, video_id: &VideoId) -> Option<&mut Video> {
+        self.channel_by_id_mut(&video_id.channel_id)?
+            .video_mut(video_id)
     }
 
-    pub fn channel(&self, channel_index: ChannelIndex) -> Option<&Channel> {
-        self.get(*channel_index)
+    /// A video along with the channel it is in
+    pub fn channel_video(&self, video_id: &VideoId) -> Option<(&Channel, &Video)> {
+        let channel = self.channel_by_id(&video_id.channel_id)?;
+        Some((channel, channel.video(video_id)?))
     }
 
     pub fn channel_by_id(&self, channel_id: &str) -> Option<&Channel> {
@@ -97,6 +204,38 @@ impl Channels {
     pub fn has_channel(&self, channel_id: &str) -> bool {
         self.channel_by_id(channel_id).is_some()
     }
+
+    /// Adds a newly subscribed channel, with the progress of any of its videos played before subscribing
+    /// and the filter rules applied
+    pub fn subscribe(&mut self, mut channel: Channel, progress: &ProgressStore, filters: &Filters) {
+        channel
+            .videos
+            .iter_mut()
+            .for_each(|video| progress.hydrate_video(video));
+        filters.apply_channel(&mut channel);
+        self.push(channel);
+    }
+
+    /// Every upcoming premiere and livestream across all channels, soonest first
+    pub fn upcoming_mixed(&self) -> Vec<(&Channel, &Video)> {
+        self.iter()
+            .flat_map(|channel| channel.upcoming.iter().map(move |video| (channel, video)))
+            .sorted_by_key(|(_, video)| video.upload)
+            .collect()
+    }
+
+    /// Every video across all channels, newest first
+    pub fn videos_mixed(&self) -> Vec<(VideoId, &Channel, &Video)> {
+        self.iter()
+            .flat_map(|channel| {
+                channel
+                    .videos
+                    .iter()
+                    .map(move |video| (channel.video_id(video), channel, video))
+            })
+            .sorted_by_key(|(_, _, video)| Reverse(video.upload))
+            .collect()
+    }
 }
 
 impl Deref for Channels {
@@ -113,8 +252,8 @@ impl DerefMut for Channels {
     }
 }
 
-impl Deref for ChannelIndex {
-    type Target = usize;
+impl Deref for ChannelId {
+    type Target = String;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -123,23 +262,137 @@ impl Deref for ChannelIndex {
 
 impl Channel {
     pub fn new(name: impl Into<String>, id: impl Into<String>, videos: Vec<Video>) -> Channel {
+        let (upcoming, videos): (Vec<Video>, Vec<Video>) =
+            videos.into_iter().partition(|video| video.upcoming);
         let videos = videos
             .into_iter()
             .sorted_by(|a, b| b.upload.cmp(&a.upload))
-            .collect();
+            .collect::<Vec<Video>>();
+        let known = videos.iter().map(|video| video.id.clone()).collect();
         Channel {
             name: name.into(),
             id: id.into(),
+            kind: SubscriptionKind::Channel,
             videos,
+            upcoming: upcoming
+                .into_iter()
+                .sorted_by_key(|video| video.upload)
+                .collect(),
+            // Channels are built from a freshly fetched feed. Cached channels have this replaced on load
+            refresh: RefreshStatus {
+                last_refreshed: Some(Local::now()),
+                last_error: None,
+            },
+            excluded_from_mixed: false,
+            known,
+        }
+    }
+
+    /// A subscription of any kind, built from its freshly fetched feed
+    pub fn from_info(info: &ChannelInfo, videos: Vec<Video>) -> Channel {
+        Channel {
+            kind: info.kind,
+            ..Channel::new(info.name.clone(), info.id.clone(), videos)
         }
     }
 
-    pub fn video_mut(&mut self, index: VideoIndex) -> Option<&mut Video> {
-        self.videos.get_mut(index.video_index)
+    /// IDs of the videos listed under one of the channel's tabs
+    pub fn known_ids(&self, tab: ChannelTab) -> HashSet<String> {
+        self.videos
+            .iter()
+            .filter(|video| video.tab == tab)
+            .map(|video| video.id.clone())
+            .collect()
+    }
+
+    /// Only counts videos from the videos tab, as streams and shorts are only shown in the channel's feed.
+    /// Videos hidden by the filter rules are left out, as the new feed hides them too
+    pub fn unseen_count(&self) -> usize {
+        self.videos
+            .iter()
+            .filter(|video| !video.seen && !video.hidden && video.tab == ChannelTab::Videos)
+            .count()
     }
 
-    pub fn video(&self, index: VideoIndex) -> Option<&Video> {
-        self.videos.get(index.video_index)
+    /// Marks every video as seen, returning false if there were none to mark
+    pub fn mark_seen(&mut self) -> bool {
+        let mut marked = false;
+        self.videos
+            .iter_mut()
+            .filter(|video| !video.seen)
+            .for_each(|video| {
+                video.seen = true;
+                marked = true;
+            });
+        marked
+    }
+
+    /// Adds any videos not already in the channel, keeping newest first. Returns how many were new.
+    /// Upcoming videos replace their older schedule, and those past their start that were not fetched again are dropped
+    pub fn add_videos(
+        &mut self,
+        videos: Vec<Video>,
+        progress: &ProgressStore,
+        filters: &Filters,
+    ) -> usize {
+        let (upcoming, videos): (Vec<Video>, Vec<Video>) =
+            videos.into_iter().partition(|video| video.upcoming);
+
+        let mut added = 0;
+        for mut video in videos {
+            self.upcoming.retain(|other| other.id != video.id);
+            if self.known.insert(video.id.clone()) {
+                progress.hydrate_video(&mut video);
+                filters.mark(&mut video);
+                self.videos.push(video);
+                added += 1;
+            } else if let Some(existing) = self.videos.iter_mut().find(|other| other.id == video.id)
+            {
+                existing.update_details(&video);
+                filters.mark(existing);
+            }
+        }
+        self.videos.sort_by_key(|video| Reverse(video.upload));
+
+        let now = Local::now();
+        self.upcoming.retain(|video| {
+            video.upload > now && !upcoming.iter().any(|other| other.id == video.id)
+        });
+        self.upcoming.extend(upcoming.into_iter().map(|mut video| {
+            filters.mark(&mut video);
+            video
+        }));
+        self.upcoming.sort_by_key(|video| video.upload);
+
+        added
+    }
+
+    pub fn video_mut(&mut self, video_id: &VideoId) -> Option<&mut Video> {
+        self.videos
+            .iter_mut()
+            .find(|video| video.id == video_id.video_id)
+    }
+
+    pub fn video(&self, video_id: &VideoId) -> Option<&Video> {
+        self.videos
+            .iter()
+            .find(|video| video.id == video_id.video_id)
+    }
+
+    pub fn video_id(&self, video: &Video) -> VideoId {
+        VideoId {
+            channel_id: self.id.clone(),
+            video_id: video.id.clone(),
+        }
+    }
+}
+
+impl RefreshStatus {
+    /// Channels that were never refreshed are always stale
+    pub fn is_stale(&self, minutes: u64) -> bool {
+        self.last_refreshed.is_none_or(|refreshed| {
+            Local::now().signed_duration_since(refreshed).num_minutes() >= minutes as i64
+        })
     }
 }
 
@@ -156,18 +409,102 @@ impl Video {
             upload: upload_date,
             progress: None,
             description: description.into(),
+            seen: true,
+            watched: false,
+            tab: ChannelTab::Videos,
+            upcoming: false,
+            duration: None,
+            view_count: None,
+            hidden: false,
         }
     }
 
     pub fn url(&self) -> String {
         format!("{}{}", "https://www.youtube.com/watch?v=", self.id)
     }
+
+    /// Saves progress from playing, marking the video watched once it passes `watched_percent` of its duration
+    pub fn set_progress(&mut self, progress: Option<WatchProgress>, watched_percent: u32) {
+        if let Some(progress) = progress
+            && progress.duration > 0
+            && progress.current as u64 * 100 >= progress.duration as u64 * watched_percent as u64
+        {
+            self.watched = true;
+        }
+        self.progress = progress;
+    }
+
+    pub fn watch_status(&self) -> String {
+        if self.watched {
+            "Watched".to_owned()
+        } else {
+            self.progress
+                .as_ref()
+                .map(|progress| {
+                    format!(
+                        "{} ({} Left)",
+                        progress.formatted(),
+                        seconds_formatted(progress.duration.saturating_sub(progress.current))
+                    )
+                })
+                .unwrap_or("Not Watched".to_owned())
+        }
+    }
+
+    /// Length and view count, for whichever are known
+    pub fn details(&self) -> Option<String> {
+        details_formatted(self.duration, self.view_count)
+    }
+
+    /// Takes the length and view count from a newer listing of the same video, keeping any it leaves out
+    pub fn update_details(&mut self, newer: &Video) {
+        self.duration = newer.duration.or(self.duration);
+        self.view_count = newer.view_count.or(self.view_count);
+    }
+
+    /// Seconds left to watch, from the saved progress or else the whole length
+    pub fn remaining(&self) -> Option<u32> {
+        self.progress
+            .map(|progress| progress.duration.saturating_sub(progress.current))
+            .or(self.duration)
+    }
 }
 
 impl VideoInfo {
     pub fn url(&self) -> String {
         format!("{}{}", "https://www.youtube.com/watch?v=", self.id)
     }
+
+    /// Length and view count, for whichever are known
+    pub fn details(&self) -> Option<String> {
+        details_formatted(self.duration, self.view_count)
+    }
+}
+
+impl ChannelInfo {
+    pub fn url(&self) -> String {
+        match self.kind {
+            SubscriptionKind::Channel => {
+                format!("{}{}", "https://www.youtube.com/channel/", self.id)
+            }
+            SubscriptionKind::Playlist => {
+                format!("{}{}", "https://www.youtube.com/playlist?list=", self.id)
+            }
+        }
+    }
+
+    pub fn feed_url(&self) -> String {
+        match self.kind {
+            SubscriptionKind::Channel => format!(
+                "{}{}",
+                "https://www.youtube.com/feeds/videos.xml?channel_id=", self.id
+            ),
+            SubscriptionKind::Playlist => format!(
+                "{}{}",
+                "https://www.youtube.com/feeds/videos.xml?playlist_id=", self.id
+            ),
+        }
+    }
 }
 
 impl From<Channel> for ChannelInfo {
@@ -175,6 +512,7 @@ impl From<Channel> for ChannelInfo {
         ChannelInfo {
             name: value.name,
             id: value.id,
+            kind: value.kind,
         }
     }
 }
@@ -184,13 +522,14 @@ impl From<&Channel> for ChannelInfo {
         ChannelInfo {
             name: value.name.clone(),
             id: value.id.clone(),
+            kind: value.kind,
         }
     }
 }
 
-impl From<VideoIndex> for ChannelIndex {
-    fn from(value: VideoIndex) -> Self {
-        ChannelIndex(value.channel_index)
+impl From<&Channel> for ChannelId {
+    fn from(value: &Channel) -> Self {
+        ChannelId(value.id.clone())
     }
 }
 
@@ -203,11 +542,22 @@ impl VideoAccumulator {
         } else if key.eq("id") {
             self.id = Some(value.as_str().unwrap().to_owned());
         } else if key.eq("timestamp") {
-            self.upload = Some(
-                DateTime::from_timestamp(value.as_i64().unwrap_or(0), 0)
-                    .unwrap()
-                    .with_timezone(&Local),
-            );
+            // Upcoming videos have no timestamp until they go live
+            self.upload = value
+                .as_i64()
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .map(|upload| upload.with_timezone(&Local));
+        } else if key.eq("release_timestamp") {
+            self.release = value
+                .as_i64()
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .map(|release| release.with_timezone(&Local));
+        } else if key.eq("duration") {
+            self.duration = value.as_f64().map(|duration| duration as u32);
+        } else if key.eq("view_count") {
+            self.view_count = value.as_u64();
+        } else if key.eq("live_status") {
+            self.live_status = value.as_str().map(|status| status.to_owned());
         } else if key.eq("availability") {
             self.available = value.is_null();
         } else if key.eq("description") {
@@ -223,70 +573,172 @@ impl VideoAccumulator {
 impl TryFrom<VideoAccumulator> for Video {
     type Error = Error;
     fn try_from(value: VideoAccumulator) -> Result<Self, Error> {
-        if !value.available || value.is_short {
+        // Shorts are only kept when they were asked for
+        if !value.available || (value.is_short && value.tab != ChannelTab::Shorts) {
             return Err(Error::VideoNotAvailable);
         }
-        Ok(Video::new(
-            value.title.ok_or(Error::VideoParsing)?,
-            value.id.ok_or(Error::VideoParsing)?,
-            value.decription.ok_or(Error::VideoParsing)?,
-            value.upload.ok_or(Error::VideoParsing)?,
-        ))
-    }
-}
-
-pub fn fetch_channel_feed(
-    channel: &str,
-    count: usize,
-    start: Option<usize>,
-) -> Result<Vec<Video>, Error> {
-    let cmd = Command::new("yt-dlp")
-        .arg("--playlist-items")
-        .arg(format!(
-            "{}:{}",
-            start.unwrap_or(1),
-            start.unwrap_or(1) + count
-        ))
-        .arg("--flat-playlist")
-        .arg("--dump-json")
-        .arg(format!(
-            "https://www.youtube.com/channel/{}/videos",
-            channel
-        ))
-        .arg("--extractor-args")
-        .arg("youtubetab:approximate_date")
-        .output()
-        .map_err(|e| Error::CommandFailed(e.to_string()))?;
-
-    let videos: Vec<Video> = String::from_utf8_lossy(&cmd.stdout)
-        .to_string()
+
+        // Upcoming videos are dated by their scheduled start rather than when they were announced
+        let upcoming = value.live_status.as_deref() == Some("is_upcoming");
+        let upload = if upcoming {
+            value.release.or(value.upload).ok_or(Error::VideoParsing)?
+        } else {
+            // Dated at the epoch when yt-dlp lists no date at all, so the video is still kept
+            value
+                .upload
+                .or(value.release)
+                .unwrap_or(DateTime::UNIX_EPOCH.with_timezone(&Local))
+        };
+
+        Ok(Video {
+            tab: value.tab,
+            upcoming,
+            duration: value.duration,
+            view_count: value.view_count,
+            ..Video::new(
+                value.title.ok_or(Error::VideoParsing)?,
+                value.id.ok_or(Error::VideoParsing)?,
+                value.decription.ok_or(Error::VideoParsing)?,
+                upload,
+            )
+        })
+    }
+}
+
+impl VideoWatchLater {
+    pub fn video_id(&self) -> VideoId {
+        VideoId {
+            channel_id: self.channel.id.clone(),
+            video_id: self.video.id.clone(),
+        }
+    }
+
+    /// Whether this is the video an ID refers to. A video is only ever saved to watch later once
+    pub fn is(&self, video_id: &VideoId) -> bool {
+        self.video.id == video_id.video_id
+    }
+}
+
+impl ChannelTab {
+    /// Every tab, in the order they are shown
+    pub const ALL: [ChannelTab; 3] = [ChannelTab::Videos, ChannelTab::Streams, ChannelTab::Shorts];
+
+    /// The tab's path on a channel's page
+    pub fn path(&self) -> &'static str {
+        match self {
+            ChannelTab::Videos => "videos",
+            ChannelTab::Streams => "streams",
+            ChannelTab::Shorts => "shorts",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChannelTab::Videos => "Videos",
+            ChannelTab::Streams => "Streams",
+            ChannelTab::Shorts => "Shorts",
+        }
+    }
+}
+
+/// Extracts a channel ID from either a channel page or a channel feed URL
+pub fn channel_id_from_url(url: &str) -> Option<String> {
+    let id = if let Some((_, query)) = url.split_once("channel_id=") {
+        query.split('&').next()
+    } else if let Some((_, path)) = url.split_once("/channel/") {
+        path.split(['/', '?']).next()
+    } else {
+        None
+    }?;
+
+    if id.starts_with("UC") {
+        Some(id.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Extracts a playlist ID from a playlist page, playlist feed or a video playing from a playlist URL.
+/// Mixes and the liked and watch later lists are made for each viewer rather than followed, so they are left out
+pub fn playlist_id_from_url(url: &str) -> Option<String> {
+    let (path, query) = url.split_once('?')?;
+    let key = if path.ends_with("/playlist") || path.ends_with("/watch") {
+        "list"
+    } else if path.ends_with("/feeds/videos.xml") {
+        "playlist_id"
+    } else {
+        return None;
+    };
+
+    let id = query
+        .split(['&', '#'])
+        .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))?;
+
+    if id.is_empty() || id.starts_with("RD") || id == "LL" || id == "WL" {
+        None
+    } else {
+        Some(id.to_owned())
+    }
+}
+
+/// Parses the line separated JSON of `yt-dlp --flat-playlist --dump-json` into videos from one of a channel's tabs.
+/// Only fails when nothing was listed, so a page of nothing but shorts or unavailable videos is still a page
+pub fn parse_channel_tab(raw: &str, tab: ChannelTab) -> Result<Vec<Video>, Error> {
+    let entries: Vec<Value> = raw
         .trim()
         .lines()
         .filter_map(|line| -> Option<Value> { serde_json::from_str(line).ok() })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(Error::VideoParsing);
+    }
+
+    Ok(entries
+        .into_iter()
         .filter_map(|json: Value| -> Option<Video> {
+            let accumulator = VideoAccumulator {
+                tab,
+                ..Default::default()
+            };
+
             json.as_object()
                 .expect("JSON is not object")
                 .iter()
-                .fold(VideoAccumulator::default(), VideoAccumulator::accumulate)
+                .fold(accumulator, VideoAccumulator::accumulate)
                 .try_into()
                 .ok()
         })
         .unique()
-        .collect();
-
-    if videos.is_empty() {
-        Err(Error::VideoParsing)
-    } else {
-        Ok(videos)
-    }
+        .collect())
 }
 
-pub fn fetch_more_videos(config: &Config, last_index: usize, channel: &mut Channel) -> bool {
-    match fetch_channel_feed(&channel.id, config.videos_per_channel, Some(last_index)) {
+pub fn fetch_more_videos(
+    config: &Config,
+    tab: ChannelTab,
+    last_index: usize,
+    channel: &mut Channel,
+    progress: &ProgressStore,
+    filters: &Filters,
+) -> bool {
+    let feed = match tab {
+        ChannelTab::Videos => config.source().fetch_feed(
+            channel.kind,
+            &channel.id,
+            config.videos_per_channel,
+            Some(last_index),
+        ),
+        tab => config.source().fetch_channel_tab(
+            &channel.id,
+            tab,
+            config.videos_per_channel,
+            Some(last_index),
+        ),
+    };
+
+    match feed {
         Ok(feed) => {
-            feed.into_iter()
-                .for_each(|video| channel.videos.push(video));
-            channel.videos.sort_by(|a, b| b.upload.cmp(&a.upload));
+            channel.add_videos(feed, progress, filters);
             return true;
         }
         Err(err) => match err {
@@ -299,26 +751,168 @@ pub fn fetch_more_videos(config: &Config, last_index: usize, channel: &mut Chann
             Error::CommandFailed(e) => {
                 log::err(format!("Could not add in more videos for channel: '{}' with command 'yt-dlp'.\nError: {}", channel.name, e));
             }
-            err => panic!("Error: {:?}", err),
+            err => {
+                log::err(format!(
+                    "Could not add in more videos for channel: '{}'.\nError: {}",
+                    channel.name, err
+                ));
+            }
         },
     }
     false
 }
 
-pub fn fetch_video_description(video: &Video) -> Result<String, Error> {
-    let cmd = Command::new("yt-dlp")
-        .arg("--dump-json")
-        .arg(video.url())
-        .output()
-        .expect("Failed to execute yt-dlp");
-
-    let json_raw = String::from_utf8_lossy(&cmd.stdout);
-    serde_json::from_str(&json_raw)
-        .map_err(|_| Error::JsonParsing)
-        .and_then(|json: Value| {
-            json["description"]
-                .as_str()
-                .ok_or(Error::JsonParsing)
-                .map(|str| str.to_owned())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playlist_ids_come_from_playlist_urls_only() {
+        let id = |url: &str| playlist_id_from_url(url);
+
+        assert_eq!(
+            id("https://www.youtube.com/playlist?list=PLabc123").as_deref(),
+            Some("PLabc123")
+        );
+        assert_eq!(
+            id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLabc123&index=2").as_deref(),
+            Some("PLabc123")
+        );
+        assert_eq!(
+            id("https://www.youtube.com/feeds/videos.xml?playlist_id=PLabc123").as_deref(),
+            Some("PLabc123")
+        );
+
+        // Mixes and personal lists can not be subscribed to
+        assert_eq!(
+            id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=RDdQw4w9WgXcQ"),
+            None
+        );
+        assert_eq!(id("https://www.youtube.com/playlist?list=WL"), None);
+        assert_eq!(id("https://www.youtube.com/playlist?list=LL"), None);
+
+        // "list=" elsewhere in a URL or search is not a playlist
+        assert_eq!(
+            id("https://www.youtube.com/results?search_query=list=PLabc"),
+            None
+        );
+        assert_eq!(
+            id("https://www.youtube.com/watch?v=abc&playlist=PLabc"),
+            None
+        );
+        assert_eq!(id("best list=PLabc songs"), None);
+        assert_eq!(id("https://www.youtube.com/playlist?list="), None);
+    }
+
+    /// A flat playlist entry as yt-dlp lists premieres and livestreams
+    fn live_entry(id: &str, status: &str, timestamp: Option<i64>, release: Option<i64>) -> String {
+        serde_json::json!({
+            "id": id,
+            "title": id,
+            "timestamp": timestamp,
+            "release_timestamp": release,
+            "live_status": status,
+            "description": null,
+            "availability": null,
+            "url": format!("https://www.youtube.com/watch?v={}", id),
         })
+        .to_string()
+    }
+
+    fn date(timestamp: i64) -> DateTime<Local> {
+        DateTime::from_timestamp(timestamp, 0)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn upcoming_videos_are_dated_by_their_scheduled_start() {
+        let raw = [
+            live_entry("premiere", "is_upcoming", None, Some(1_900_000_000)),
+            live_entry("live", "is_live", Some(1_700_000_000), Some(1_600_000_000)),
+            live_entry("released", "was_live", None, Some(1_650_000_000)),
+            live_entry("undated", "not_live", None, None),
+            live_entry("unscheduled", "is_upcoming", None, None),
+        ]
+        .join("\n");
+
+        let videos = parse_channel_tab(&raw, ChannelTab::Videos).unwrap();
+        let video = |id: &str| videos.iter().find(|video| video.id == id);
+
+        let premiere = video("premiere").unwrap();
+        assert!(premiere.upcoming);
+        assert_eq!(premiere.upload, date(1_900_000_000));
+
+        let live = video("live").unwrap();
+        assert!(!live.upcoming);
+        assert_eq!(live.upload, date(1_700_000_000));
+
+        // Videos without a timestamp fall back to their release, or else are kept at the epoch
+        assert_eq!(video("released").unwrap().upload, date(1_650_000_000));
+        assert_eq!(video("undated").unwrap().upload, date(0));
+        assert!(video("unscheduled").is_none());
+    }
+
+    #[test]
+    fn upcoming_videos_move_into_the_feed_once_live() {
+        let premiere = |upcoming: bool, upload: DateTime<Local>| Video {
+            upcoming,
+            ..Video::new("Premiere", "premiere", "N/A", upload)
+        };
+        let start = Local::now() + chrono::Duration::hours(1);
+        let progress = ProgressStore::default();
+        let filters = Filters::default();
+
+        let mut channel = Channel::new("Channel", "UCchannel", Vec::new());
+        assert_eq!(
+            channel.add_videos(vec![premiere(true, start)], &progress, &filters),
+            0
+        );
+        assert_eq!(channel.upcoming.len(), 1);
+        assert!(channel.videos.is_empty());
+
+        let added = channel.add_videos(vec![premiere(false, Local::now())], &progress, &filters);
+        assert_eq!(added, 1);
+        assert!(channel.upcoming.is_empty());
+        assert_eq!(channel.videos[0].id, "premiere");
+    }
+
+    #[test]
+    fn shorts_are_only_kept_in_the_shorts_tab() {
+        let entry = |id: &str, path: &str| {
+            serde_json::json!({
+                "id": id,
+                "title": id,
+                "timestamp": 1_700_000_000,
+                "description": null,
+                "availability": null,
+                "url": format!("https://www.youtube.com/{}{}", path, id),
+            })
+            .to_string()
+        };
+        let raw = [entry("video", "watch?v="), entry("short", "shorts/")].join("\n");
+        let ids = |tab: ChannelTab| {
+            parse_channel_tab(&raw, tab)
+                .unwrap()
+                .into_iter()
+                .map(|video| (video.id, video.tab))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(ChannelTab::Videos),
+            vec![("video".to_owned(), ChannelTab::Videos)]
+        );
+        assert_eq!(
+            ids(ChannelTab::Streams),
+            vec![("video".to_owned(), ChannelTab::Streams)]
+        );
+        assert_eq!(
+            ids(ChannelTab::Shorts),
+            vec![
+                ("video".to_owned(), ChannelTab::Shorts),
+                ("short".to_owned(), ChannelTab::Shorts)
+            ]
+        );
+    }
 }